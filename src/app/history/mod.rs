@@ -0,0 +1,232 @@
+use super::storage::data_dir;
+use super::task_config::{Task, TaskStatus};
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_FILE: &str = "history.yaml";
+pub const RUNS_DIR: &str = "runs";
+
+// What a run directory holds
+pub const RUN_CONFIG_FILE: &str = "config.yaml";
+pub const RUN_COMMAND_FILE: &str = "command.sh";
+pub const RUN_ENV_FILE: &str = "env.txt";
+pub const RUN_LOG_FILE: &str = "output.log";
+
+/// One finished run of a task, as written to the history journal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    task_id: usize,
+    task_name: String,
+    environment: String,
+    directory: String,
+    attempt: u32,
+    command: String,
+    yaml: Option<Value>, // The config exactly as it was written for this run
+    started_at: u64,     // Unix seconds
+    ended_at: u64,
+    status: TaskStatus,
+    exit_code: Option<i32>,
+    log_path: String,
+    #[serde(default)] // Records from before run directories existed
+    run_dir: Option<String>,
+    #[serde(default)]
+    wandb_url: Option<String>,
+}
+
+impl RunRecord {
+    pub fn new(task: &Task, command: String, started_at: u64, log_path: String) -> Self {
+        Self {
+            task_id: task.get_id(),
+            task_name: task.get_task_name().to_string(),
+            environment: task.get_environment().to_string(),
+            directory: task.get_directory().to_string(),
+            attempt: task.get_attempts(),
+            command,
+            yaml: task.get_yaml().clone(),
+            started_at,
+            ended_at: started_at,
+            status: TaskStatus::Running,
+            exit_code: None,
+            log_path,
+            run_dir: task.get_run_dir().clone(),
+            wandb_url: None,
+        }
+    }
+    // Fill in how the run ended
+    pub fn finish(&mut self, status: TaskStatus, ended_at: u64) {
+        self.exit_code = match status {
+            TaskStatus::Succeeded => Some(0),
+            TaskStatus::Failed(code) => Some(code),
+            _ => None,
+        };
+        self.status = status;
+        self.ended_at = ended_at;
+    }
+
+    pub fn get_task_id(&self) -> usize {
+        self.task_id
+    }
+    pub fn get_task_name(&self) -> &str {
+        &self.task_name
+    }
+    pub fn get_environment(&self) -> &str {
+        &self.environment
+    }
+    pub fn get_directory(&self) -> &str {
+        &self.directory
+    }
+    pub fn get_attempt(&self) -> u32 {
+        self.attempt
+    }
+    pub fn get_command(&self) -> &str {
+        &self.command
+    }
+    pub fn get_yaml(&self) -> &Option<Value> {
+        &self.yaml
+    }
+    pub fn get_started_at(&self) -> u64 {
+        self.started_at
+    }
+    pub fn get_ended_at(&self) -> u64 {
+        self.ended_at
+    }
+    pub fn get_status(&self) -> &TaskStatus {
+        &self.status
+    }
+    pub fn get_exit_code(&self) -> Option<i32> {
+        self.exit_code
+    }
+    pub fn get_log_path(&self) -> &str {
+        &self.log_path
+    }
+    pub fn get_run_dir(&self) -> &Option<String> {
+        &self.run_dir
+    }
+    pub fn get_wandb_url(&self) -> &Option<String> {
+        &self.wandb_url
+    }
+    pub fn set_wandb_url(&mut self, wandb_url: Option<String>) {
+        self.wandb_url = wandb_url;
+    }
+}
+
+pub fn history_path() -> PathBuf {
+    data_dir().join(HISTORY_FILE)
+}
+
+/// Directory holding everything needed to reproduce one run,
+/// e.g. `runs/1760000000_cartpole_3-1/`.
+pub fn run_dir_for(task: &Task, started_at: u64) -> PathBuf {
+    // Task names are free text, keep the file name shell and filesystem friendly
+    let name: String = task
+        .get_task_name()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    data_dir().join(RUNS_DIR).join(format!(
+        "{}_{}_{}-{}",
+        started_at,
+        name,
+        task.get_id(),
+        task.get_attempts()
+    ))
+}
+
+/// Writes the run's config, launch command and environment into `run_dir`.
+/// `command` is a full shell line, including the `cd` into the task's directory.
+pub fn write_run_snapshot(
+    run_dir: &Path,
+    yaml: &Option<Value>,
+    command: &str,
+    env_vars: &BTreeMap<String, String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(run_dir)?;
+    if let Some(yaml) = yaml {
+        fs::write(run_dir.join(RUN_CONFIG_FILE), serde_yaml::to_string(yaml)?)?;
+    }
+    fs::write(
+        run_dir.join(RUN_COMMAND_FILE),
+        format!("#!/bin/bash\n{}\n", command),
+    )?;
+    let env: String = env_vars
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect();
+    fs::write(run_dir.join(RUN_ENV_FILE), env)?;
+    Ok(())
+}
+
+// Each record is its own YAML document, so appending never rewrites older runs
+pub fn append_record(path: &Path, record: &RunRecord) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let document = format!("---\n{}", serde_yaml::to_string(record)?);
+    let mut file = File::options().create(true).append(true).open(path)?;
+    // One write per record so concurrent workers don't interleave
+    file.write_all(document.as_bytes())?;
+    Ok(())
+}
+
+/// Reads every record in the journal, oldest first. A missing file is an empty history.
+pub fn load_history(path: &Path) -> Result<Vec<RunRecord>, Box<dyn std::error::Error>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+
+    let mut records = vec![];
+    for document in serde_yaml::Deserializer::from_str(&contents) {
+        records.push(RunRecord::deserialize(document)?);
+    }
+    Ok(records)
+}
+
+pub fn now_unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Formats unix seconds as `YYYY-MM-DD HH:MM:SS UTC`.
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let time = secs % 86_400;
+
+    // Civil-from-days (Howard Hinnant), valid for any date after 1970
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        time / 3600,
+        (time % 3600) / 60,
+        time % 60
+    )
+}
@@ -1,6 +1,22 @@
+pub mod history;
+pub mod storage;
 pub mod task_config;
-use serde_yaml::{Value, from_str};
-use task_config::Task;
+use crate::runner::checkpoints::checkpoint_iteration;
+use crate::runner::control::{CANCEL_GRACE_PERIOD, SharedControl};
+use crate::runner::log_buffer::SharedLog;
+use crate::runner::pool::DEFAULT_MAX_WORKERS;
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use task_config::{
+    ConfigMode, PythonEnv, Task, TaskKind, TaskLocation, TaskStatus, checkpoint::ResumeFrom,
+    container::ContainerSpec, directory, parse_duration, parse_env_vars, retry::RetryPolicy,
+    wandb::WandbConfig,
+};
+
+// Task outputs kept in memory; older ones are still on disk in their run directories
+const MAX_TASK_LOGS: usize = 20;
+
 #[derive(PartialEq, Debug)]
 pub enum State {
     Enter,
@@ -12,6 +28,15 @@ pub enum CreationState {
     Taskname,
     Envname,
     Dir,
+    Location,
+    Container,
+    Env,
+    PythonEnv,
+    ConfigMode,
+    Wandb,
+    Retry,
+    Timeout,
+    Depends,
     Yaml,
     Null,
 }
@@ -19,28 +44,409 @@ pub enum CreationState {
 pub struct App {
     state: State,
     creation_state: CreationState,
-    task_queue: Vec<Task>,
+    task_queue: Vec<Task>, // Every task of the session, finished ones keep their status
+    next_task_id: usize,
     template_task: Option<Task>, // Task being created before added to queue
+    location_buffer: String,     // Where the task runs, parsed on enqueue
+    container_buffer: String,    // Container image and flags, parsed on enqueue
+    env_buffer: String,          // KEY=value pairs, parsed on enqueue
+    python_env_buffer: String,   // conda env or venv to activate, parsed on enqueue
+    config_mode_buffer: String,  // file or overrides, parsed on enqueue
+    wandb_buffer: String,        // W&B on/off and project, parsed on enqueue
+    retry_buffer: String,        // Retry settings typed in the popup, parsed on enqueue
+    timeout_buffer: String,      // Timeout typed in the popup, parsed on enqueue
+    depends_buffer: String,      // Comma separated names of prerequisite tasks
+    task_logs: Vec<(usize, String, SharedLog)>, // Task id, name and latest output, oldest first
+    running_controls: Vec<(usize, SharedControl)>, // Handles on running tasks, by task id
+    max_workers: usize,          // How many tasks may run at once
+    restore_config: bool,        // Put config files back as they were once a run ends
+    queue_path: PathBuf,         // State file the queue is saved to after every change
+    storage_error: Option<String>, // Last failure to save or load the queue
 }
 
 impl App {
     pub fn new_app() -> Self {
+        let mut app = Self::with_queue_path(storage::queue_path());
+        app.restore_queue();
+        app
+    }
+    pub fn with_queue_path(queue_path: PathBuf) -> Self {
         Self {
             state: State::Enter,
             creation_state: CreationState::Null,
             task_queue: vec![],
+            next_task_id: 0,
             template_task: None,
+            location_buffer: String::from(""),
+            container_buffer: String::from(""),
+            env_buffer: String::from(""),
+            python_env_buffer: String::from(""),
+            config_mode_buffer: String::from(""),
+            wandb_buffer: String::from(""),
+            retry_buffer: String::from(""),
+            timeout_buffer: String::from(""),
+            depends_buffer: String::from(""),
+            task_logs: vec![],
+            running_controls: vec![],
+            max_workers: DEFAULT_MAX_WORKERS,
+            restore_config: true,
+            queue_path,
+            storage_error: None,
+        }
+    }
+
+    // Picks up the queue saved by a previous session
+    fn restore_queue(&mut self) {
+        match storage::load_queue(&self.queue_path) {
+            Ok(mut tasks) => {
+                for task in tasks.iter_mut() {
+                    // Nothing survives the app closing; relaunching a half-done run
+                    // on startup would be a surprise, so it counts as cancelled
+                    if *task.get_status() == TaskStatus::Running {
+                        task.set_status(TaskStatus::Cancelled);
+                    }
+                }
+                self.next_task_id = tasks
+                    .iter()
+                    .map(|task| task.get_id() + 1)
+                    .max()
+                    .unwrap_or(0);
+                self.task_queue = tasks;
+            }
+            Err(e) => {
+                // Keep the unreadable file aside instead of saving over it
+                let backup = self.queue_path.with_extension("yaml.bad");
+                let _ = std::fs::rename(&self.queue_path, &backup);
+                self.storage_error = Some(format!(
+                    "Could not restore queue ({}), moved it to {}",
+                    e,
+                    backup.display()
+                ))
+            }
         }
     }
-    pub fn task_queue_is_empty(&self) -> bool {
-        self.task_queue.is_empty()
+    fn save_queue(&mut self) {
+        self.storage_error = match storage::save_queue(&self.queue_path, &self.task_queue) {
+            Ok(_) => None,
+            Err(e) => Some(format!(
+                "Could not save {}: {}",
+                self.queue_path.display(),
+                e
+            )),
+        };
+    }
+    pub fn report_storage_error(&mut self, error: String) {
+        self.storage_error = Some(error);
     }
+    pub fn get_storage_error(&self) -> &Option<String> {
+        &self.storage_error
+    }
+    /// Marks the first queued task as running and returns a copy for the worker.
+    /// The task stays in the queue so its status can still be shown.
     pub fn pop_first_task(&mut self) -> Option<Task> {
-        if !self.task_queue.is_empty() {
-            Some(self.task_queue.remove(0))
-        } else {
-            None
+        if self.skip_tasks_with_failed_dependencies() {
+            self.save_queue();
         }
+
+        let now = Instant::now();
+        let index = self.task_queue.iter().position(|task| {
+            task.is_ready(now)
+                && task
+                    .get_dependencies()
+                    .iter()
+                    .all(|id| self.get_task_status(*id) == Some(TaskStatus::Succeeded))
+                && !self.config_in_use(task)
+        })?;
+        let task = &mut self.task_queue[index];
+        task.set_status(TaskStatus::Running);
+        task.set_attempts(task.get_attempts() + 1);
+        task.set_not_before(None);
+        let task = task.clone();
+        self.save_queue();
+        Some(task)
+    }
+
+    // Whether a running task shares the task's config file while one of them
+    // writes over it; Isaac Lab reads the file late, after the sim has started
+    fn config_in_use(&self, task: &Task) -> bool {
+        let key = config_key(task);
+        self.task_queue.iter().any(|other| {
+            *other.get_status() == TaskStatus::Running
+                && (task.get_config_mode() == ConfigMode::File
+                    || other.get_config_mode() == ConfigMode::File)
+                && config_key(other) == key
+        })
+    }
+
+    // A queued task can never run once one of its dependencies ended without succeeding
+    fn skip_tasks_with_failed_dependencies(&mut self) -> bool {
+        let mut skipped_any = false;
+        // Dependencies always sit earlier in the queue, so one pass covers chains
+        for index in 0..self.task_queue.len() {
+            let task = &self.task_queue[index];
+            if *task.get_status() != TaskStatus::Queued {
+                continue;
+            }
+            let dependency_failed = task.get_dependencies().iter().any(|id| {
+                self.get_task_status(*id)
+                    .is_some_and(|status| status.is_finished() && status != TaskStatus::Succeeded)
+            });
+            if dependency_failed {
+                self.task_queue[index].set_status(TaskStatus::Skipped);
+                skipped_any = true;
+            }
+        }
+        skipped_any
+    }
+    pub fn get_task_status(&self, task_id: usize) -> Option<TaskStatus> {
+        self.task_queue
+            .iter()
+            .find(|task| task.get_id() == task_id)
+            .map(|task| *task.get_status())
+    }
+
+    /// Turns the popup's comma separated task names into task ids, newest task
+    /// first when a name was queued more than once.
+    fn resolve_dependencies(&self, names: &str) -> Result<Vec<usize>, String> {
+        let mut dependencies = vec![];
+        for name in names.split(',').map(|name| name.trim()) {
+            if name.is_empty() {
+                continue;
+            }
+            match self
+                .task_queue
+                .iter()
+                .rev()
+                .find(|task| task.get_task_name() == name)
+            {
+                Some(task) => dependencies.push(task.get_id()),
+                None => return Err(format!("No queued task named '{}' to depend on", name)),
+            }
+        }
+        Ok(dependencies)
+    }
+
+    /// Puts a failed task back in the queue if its retry policy allows another attempt.
+    pub fn retry_task(&mut self, task_id: usize, outcome: &TaskStatus) -> bool {
+        let Some(task) = self
+            .task_queue
+            .iter_mut()
+            .find(|task| task.get_id() == task_id)
+        else {
+            return false;
+        };
+        let Some(retry) = task.get_retry().clone() else {
+            return false;
+        };
+        if !retry.should_retry(task.get_attempts(), outcome) {
+            return false;
+        }
+
+        task.set_status(TaskStatus::Queued);
+        // A delay too large for `Instant` (only from a hand-edited queue file) retries now
+        task.set_not_before(Instant::now().checked_add(retry.get_delay()));
+        let note = format!(
+            "Retrying in {}s (attempt {}/{})",
+            retry.get_delay().as_secs(),
+            task.get_attempts() + 1,
+            retry.get_max_attempts()
+        );
+        if let Some(log) = self.get_task_log(task_id) {
+            log.lock().unwrap().push_line(note);
+        }
+        self.save_queue();
+        true
+    }
+    pub fn set_task_status(&mut self, task_id: usize, status: TaskStatus) {
+        if let Some(task) = self
+            .task_queue
+            .iter_mut()
+            .find(|task| task.get_id() == task_id)
+        {
+            task.set_status(status);
+        }
+        self.save_queue();
+    }
+    pub fn set_task_run_dir(&mut self, task_id: usize, run_dir: String) {
+        if let Some(task) = self
+            .task_queue
+            .iter_mut()
+            .find(|task| task.get_id() == task_id)
+        {
+            task.set_run_dir(Some(run_dir));
+        }
+        self.save_queue();
+    }
+    pub fn get_task_at(&self, index: usize) -> Option<&Task> {
+        self.task_queue.get(index)
+    }
+    /// Queues a copy of the task at `index` that continues training from a
+    /// checkpoint, and returns the new task's name.
+    pub fn queue_resume_task(&mut self, index: usize, resume: ResumeFrom) -> Option<String> {
+        let suffix = checkpoint_suffix("resume", resume.get_checkpoint());
+        self.queue_derived_task(index, &suffix, |task| {
+            // Resuming a play task's checkpoint means training from it again
+            task.set_kind(TaskKind::Train);
+            task.set_resume(Some(resume));
+        })
+    }
+    /// Queues an evaluation of the task at `index` that plays the policy in
+    /// `checkpoint`, and returns the new task's name.
+    pub fn queue_play_task(
+        &mut self,
+        index: usize,
+        checkpoint: String,
+        video: bool,
+    ) -> Option<String> {
+        let name = Path::new(&checkpoint)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let suffix = checkpoint_suffix(if video { "video" } else { "play" }, &name);
+        self.queue_derived_task(index, &suffix, |task| {
+            task.set_kind(TaskKind::Play { checkpoint, video });
+            task.set_resume(None);
+            task.set_wandb(None); // Nothing to log, and play.py would start an empty W&B run
+        })
+    }
+    // Queues a copy of the task at `index` named `<name>-<suffix>`, changed by `derive`
+    fn queue_derived_task<F>(&mut self, index: usize, suffix: &str, derive: F) -> Option<String>
+    where
+        F: FnOnce(&mut Task),
+    {
+        let mut task = self.task_queue.get(index)?.clone();
+        task.set_task_name(format!("{}-{}", task.get_task_name(), suffix));
+        derive(&mut task);
+        // A fresh run of its own, not waiting on what the original waited on
+        task.set_dependencies(vec![]);
+        task.set_run_dir(None);
+        task.set_wandb_url(None);
+        task.set_not_before(None);
+        let task_name = task.get_task_name().to_string();
+        self.add_task_to_queue(task);
+        Some(task_name)
+    }
+    /// Copies W&B run URLs that running tasks have printed onto the tasks.
+    pub fn refresh_wandb_urls(&mut self) {
+        let mut changed = false;
+        for task in self.task_queue.iter_mut() {
+            if *task.get_status() != TaskStatus::Running || task.get_wandb().is_none() {
+                continue;
+            }
+            let url = self
+                .task_logs
+                .iter()
+                .find(|(id, _, _)| *id == task.get_id())
+                .and_then(|(_, _, log)| log.lock().unwrap().get_wandb_url().map(String::from));
+            if url.is_some() && url != *task.get_wandb_url() {
+                task.set_wandb_url(url);
+                changed = true;
+            }
+        }
+        if changed {
+            self.save_queue();
+        }
+    }
+    pub fn get_task_run_dir_at(&self, index: usize) -> Option<&str> {
+        self.task_queue.get(index)?.get_run_dir().as_deref()
+    }
+    pub fn get_task_statuses(&self) -> Vec<TaskStatus> {
+        self.task_queue
+            .iter()
+            .map(|task| *task.get_status())
+            .collect()
+    }
+    /// Scheduler-side state of each task (e.g. a Slurm job waiting in the
+    /// queue), for tasks whose executor reports one.
+    pub fn get_task_remote_states(&self) -> Vec<Option<String>> {
+        self.task_queue
+            .iter()
+            .map(|task| {
+                self.running_controls
+                    .iter()
+                    .find(|(id, _)| *id == task.get_id())
+                    .and_then(|(_, control)| control.get_remote_state())
+            })
+            .collect()
+    }
+    /// Keeps the latest log of each task, and at most `MAX_TASK_LOGS` in all.
+    /// Logs go by task id, since two queued tasks may share a name.
+    pub fn add_task_log(&mut self, task: &Task, log: SharedLog) {
+        self.task_logs.retain(|(id, _, _)| *id != task.get_id());
+        if self.task_logs.len() >= MAX_TASK_LOGS {
+            self.task_logs.remove(0);
+        }
+        self.task_logs
+            .push((task.get_id(), task.get_task_name().to_string(), log));
+    }
+    pub fn get_task_log(&self, task_id: usize) -> Option<&SharedLog> {
+        self.task_logs
+            .iter()
+            .find(|(id, _, _)| *id == task_id)
+            .map(|(_, _, log)| log)
+    }
+    pub fn get_latest_log(&self) -> Option<(usize, &str, &SharedLog)> {
+        self.task_logs
+            .last()
+            .map(|(id, name, log)| (*id, name.as_str(), log))
+    }
+    pub fn add_running_control(&mut self, task_id: usize, control: SharedControl) {
+        self.running_controls.push((task_id, control));
+    }
+    pub fn remove_running_control(&mut self, task_id: usize) {
+        self.running_controls.retain(|(id, _)| *id != task_id);
+    }
+    /// Cancels the task at `index` in the queue if it is running.
+    pub fn cancel_task_at(&mut self, index: usize) -> bool {
+        let Some(task) = self.task_queue.get(index) else {
+            return false;
+        };
+        match self
+            .running_controls
+            .iter()
+            .find(|(id, _)| *id == task.get_id())
+        {
+            Some((_, control)) => {
+                control.cancel(CANCEL_GRACE_PERIOD);
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn cancel_all_tasks(&mut self) {
+        for (_, control) in &self.running_controls {
+            control.cancel(CANCEL_GRACE_PERIOD);
+        }
+    }
+    /// Drops tasks that have ended from the queue; the run history keeps them.
+    /// Ones a waiting task depends on stay, so it can still tell how they went.
+    pub fn clear_finished_tasks(&mut self) -> usize {
+        let needed: Vec<usize> = self
+            .task_queue
+            .iter()
+            .filter(|task| !task.get_status().is_finished())
+            .flat_map(|task| task.get_dependencies().iter().copied())
+            .collect();
+        let before = self.task_queue.len();
+        self.task_queue
+            .retain(|task| !task.get_status().is_finished() || needed.contains(&task.get_id()));
+        let removed = before - self.task_queue.len();
+        if removed > 0 {
+            self.save_queue();
+        }
+        removed
+    }
+    pub fn get_restore_config(&self) -> bool {
+        self.restore_config
+    }
+    pub fn set_restore_config(&mut self, restore_config: bool) {
+        self.restore_config = restore_config;
+    }
+    pub fn get_max_workers(&self) -> usize {
+        self.max_workers
+    }
+    pub fn set_max_workers(&mut self, max_workers: usize) {
+        self.max_workers = max_workers.max(1);
     }
     pub fn set_yaml(&mut self, yaml: Value) {
         if let Some(ref mut task) = self.template_task {
@@ -62,6 +468,15 @@ impl App {
     }
     pub fn create_new_template_task(&mut self) {
         self.creation_state = CreationState::Taskname;
+        self.location_buffer.clear();
+        self.container_buffer.clear();
+        self.env_buffer.clear();
+        self.python_env_buffer.clear();
+        self.config_mode_buffer.clear();
+        self.wandb_buffer.clear();
+        self.retry_buffer.clear();
+        self.timeout_buffer.clear();
+        self.depends_buffer.clear();
         let temp_task = Task::new();
         self.template_task = Some(temp_task);
     }
@@ -74,12 +489,81 @@ impl App {
         None
     }
 
-    pub fn pass_template_to_task_list(&mut self) {
-        match self.template_task.take() {
-            Some(task) => self.add_task_to_queue(task),
-            None => (),
-        }
+    /// Queues the task from the create popup, or says which field is invalid.
+    pub fn pass_template_to_task_list(&mut self) -> Result<(), String> {
+        let task = self.build_template_task()?;
         self.template_task = None;
+        self.add_task_to_queue(task);
+        Ok(())
+    }
+
+    /// The task the create popup would queue, with every field parsed and
+    /// checked, without queueing it.
+    pub fn build_template_task(&self) -> Result<Task, String> {
+        let location = TaskLocation::parse(&self.location_buffer)?;
+        let container = ContainerSpec::parse(&self.container_buffer)?;
+        let env_vars = parse_env_vars(&self.env_buffer)?;
+        let python_env = PythonEnv::parse(&self.python_env_buffer)?;
+        let config_mode = ConfigMode::parse(&self.config_mode_buffer)?;
+        let wandb = WandbConfig::parse(&self.wandb_buffer)?;
+        let retry = RetryPolicy::parse(&self.retry_buffer)?;
+        let timeout = parse_duration(&self.timeout_buffer)?;
+        let dependencies = self.resolve_dependencies(&self.depends_buffer)?;
+        let Some(mut task) = self.template_task.clone() else {
+            return Err(String::from("No task is being created"));
+        };
+        // Remote paths expand on the remote side, `validate_isaaclab_dir_on` checks them there
+        if !matches!(location, TaskLocation::Ssh(_)) {
+            let directory = directory::expand_path(task.get_directory())?;
+            directory::validate_isaaclab_dir(&directory)?;
+            task.set_directory(directory.to_string_lossy().to_string());
+        }
+        task.set_dependencies(dependencies);
+        task.set_location(location);
+        task.set_container(container);
+        task.set_env_vars(env_vars);
+        task.set_python_env(python_env);
+        task.set_config_mode(config_mode);
+        task.set_wandb(wandb);
+        task.set_retry(retry);
+        task.set_timeout(timeout);
+        Ok(task)
+    }
+    pub fn get_location_buffer(&self) -> &str {
+        &self.location_buffer
+    }
+    pub fn get_container_buffer(&self) -> &str {
+        &self.container_buffer
+    }
+    pub fn get_env_buffer(&self) -> &str {
+        &self.env_buffer
+    }
+    pub fn get_python_env_buffer(&self) -> &str {
+        &self.python_env_buffer
+    }
+    pub fn get_config_mode_buffer(&self) -> &str {
+        &self.config_mode_buffer
+    }
+    pub fn get_wandb_buffer(&self) -> &str {
+        &self.wandb_buffer
+    }
+    // "Task Location" line for the Info panel, with the container if there is one
+    pub fn get_task_location_label_at(&self, index: usize) -> Option<String> {
+        let task = self.task_queue.get(index)?;
+        let label = task.get_location().label();
+        Some(match task.get_container() {
+            Some(container) => format!("{} ({})", label, container.label()),
+            None => label,
+        })
+    }
+    pub fn get_retry_buffer(&self) -> &str {
+        &self.retry_buffer
+    }
+    pub fn get_timeout_buffer(&self) -> &str {
+        &self.timeout_buffer
+    }
+    pub fn get_depends_buffer(&self) -> &str {
+        &self.depends_buffer
     }
 
     pub fn get_task_queue_names(&self) -> Vec<&str> {
@@ -90,8 +574,13 @@ impl App {
         task_queue_names
     }
 
-    pub fn add_task_to_queue(&mut self, task: Task) {
+    pub fn add_task_to_queue(&mut self, mut task: Task) {
+        task.set_id(self.next_task_id);
+        task.set_status(TaskStatus::Queued);
+        task.set_attempts(0);
+        self.next_task_id += 1;
         self.task_queue.push(task);
+        self.save_queue();
     }
 
     pub fn task_template_task(&mut self) -> Option<Task> {
@@ -101,31 +590,44 @@ impl App {
         &self.template_task
     }
 
+    // Text of the field being edited in the create popup, if it is a text field
+    fn buffer_mut(&mut self) -> Option<&mut String> {
+        match self.creation_state {
+            CreationState::Taskname => self.template_task.as_mut().map(Task::task_name_mut),
+            CreationState::Envname => self.template_task.as_mut().map(Task::environment_mut),
+            CreationState::Dir => self.template_task.as_mut().map(Task::directory_mut),
+            CreationState::Location => Some(&mut self.location_buffer),
+            CreationState::Container => Some(&mut self.container_buffer),
+            CreationState::Env => Some(&mut self.env_buffer),
+            CreationState::PythonEnv => Some(&mut self.python_env_buffer),
+            CreationState::ConfigMode => Some(&mut self.config_mode_buffer),
+            CreationState::Wandb => Some(&mut self.wandb_buffer),
+            CreationState::Retry => Some(&mut self.retry_buffer),
+            CreationState::Timeout => Some(&mut self.timeout_buffer),
+            CreationState::Depends => Some(&mut self.depends_buffer),
+            CreationState::Yaml | CreationState::Null => None,
+        }
+    }
+
     pub fn write_to_buffer(&mut self, c: char) {
-        // Copy out the enum (no borrow of self)!
-        let creation_state = *self.get_creation_state();
-
-        if let Some(task) = self.template_task.as_mut() {
-            if creation_state == CreationState::Taskname {
-                let mut name = task.get_task_name().to_string();
-                name.push(c);
-                task.set_task_name(name);
-            } else if creation_state == CreationState::Envname {
-                let mut env_name = task.get_environment().to_string();
-                env_name.push(c);
-                task.set_environment(env_name);
-            } else if creation_state == CreationState::Dir {
-                let mut directory = task.get_directory().to_string();
-                directory.push(c);
-                task.set_directory(directory);
-            }
+        if let Some(buffer) = self.buffer_mut() {
+            buffer.push(c);
         }
     }
     pub fn move_down_fsm(&mut self) {
         match self.creation_state {
             CreationState::Taskname => self.creation_state = CreationState::Envname,
             CreationState::Envname => self.creation_state = CreationState::Dir,
-            CreationState::Dir => self.creation_state = CreationState::Yaml,
+            CreationState::Dir => self.creation_state = CreationState::Location,
+            CreationState::Location => self.creation_state = CreationState::Container,
+            CreationState::Container => self.creation_state = CreationState::Env,
+            CreationState::Env => self.creation_state = CreationState::PythonEnv,
+            CreationState::PythonEnv => self.creation_state = CreationState::ConfigMode,
+            CreationState::ConfigMode => self.creation_state = CreationState::Wandb,
+            CreationState::Wandb => self.creation_state = CreationState::Retry,
+            CreationState::Retry => self.creation_state = CreationState::Timeout,
+            CreationState::Timeout => self.creation_state = CreationState::Depends,
+            CreationState::Depends => self.creation_state = CreationState::Yaml,
             CreationState::Yaml => self.creation_state = CreationState::Taskname,
             _ => (),
         }
@@ -138,7 +640,16 @@ impl App {
         match self.creation_state {
             CreationState::Taskname => self.creation_state = CreationState::Envname,
             CreationState::Envname => self.creation_state = CreationState::Dir,
-            CreationState::Dir => self.creation_state = CreationState::Yaml,
+            CreationState::Dir => self.creation_state = CreationState::Location,
+            CreationState::Location => self.creation_state = CreationState::Container,
+            CreationState::Container => self.creation_state = CreationState::Env,
+            CreationState::Env => self.creation_state = CreationState::PythonEnv,
+            CreationState::PythonEnv => self.creation_state = CreationState::ConfigMode,
+            CreationState::ConfigMode => self.creation_state = CreationState::Wandb,
+            CreationState::Wandb => self.creation_state = CreationState::Retry,
+            CreationState::Retry => self.creation_state = CreationState::Timeout,
+            CreationState::Timeout => self.creation_state = CreationState::Depends,
+            CreationState::Depends => self.creation_state = CreationState::Yaml,
             CreationState::Yaml => self.creation_state = CreationState::Taskname,
             _ => (),
         }
@@ -146,15 +657,28 @@ impl App {
 
     pub fn pop_last_elem_from_buffer(&mut self) {
         // Used for backspace <----------
-        // Copy out the enum (no borrow of self)!
-        let creation_state = *self.get_creation_state();
-
-        if let Some(task) = self.template_task.as_mut() {
-            if creation_state == CreationState::Taskname {
-                let mut name = task.get_task_name().to_string();
-                name.pop();
-                task.set_task_name(name);
-            }
+        if let Some(buffer) = self.buffer_mut() {
+            buffer.pop();
         }
     }
 }
+
+// `<action>-<iteration>` for a `model_<iteration>.pt`, plain `<action>` otherwise
+// Host and path of a task's config file, without asking a remote host.
+// Slurm jobs read their config from this machine's (shared) filesystem.
+fn config_key(task: &Task) -> (Option<&str>, PathBuf) {
+    let path = Path::new(task.get_directory()).join(task.get_config_file());
+    match task.get_location() {
+        TaskLocation::Ssh(host) => (Some(host.as_str()), path),
+        TaskLocation::Local | TaskLocation::Slurm(_) => {
+            (None, std::path::absolute(&path).unwrap_or(path))
+        }
+    }
+}
+
+fn checkpoint_suffix(action: &str, checkpoint: &str) -> String {
+    match checkpoint_iteration(checkpoint) {
+        Some(iteration) => format!("{}-{}", action, iteration),
+        None => String::from(action),
+    }
+}
@@ -0,0 +1,58 @@
+use super::task_config::Task;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+pub const QUEUE_FILE: &str = "queue.yaml";
+pub const BACKUPS_DIR: &str = "backups"; // Originals of config files runs wrote over
+
+/// Directory the tool keeps its own state in: `$PUSHING_LAUNCH_HOME`, or
+/// `~/.pushing_launch` when that isn't set.
+pub fn data_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("PUSHING_LAUNCH_HOME")
+        && !dir.is_empty()
+    {
+        return PathBuf::from(dir);
+    }
+    match std::env::var("HOME") {
+        Ok(home) => Path::new(&home).join(".pushing_launch"),
+        Err(_) => PathBuf::from(".pushing_launch"),
+    }
+}
+
+pub fn queue_path() -> PathBuf {
+    data_dir().join(QUEUE_FILE)
+}
+
+pub fn backups_dir() -> PathBuf {
+    data_dir().join(BACKUPS_DIR)
+}
+
+// Write the whole queue to a temp file and rename it over the old one,
+// so a crash mid-write never leaves a truncated state file behind
+pub fn save_queue(path: &Path, tasks: &[Task]) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let yaml_string = serde_yaml::to_string(tasks)?;
+
+    let tmp_path = path.with_extension("yaml.tmp");
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(yaml_string.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+/// Reads a saved queue back. A missing file is just an empty queue.
+pub fn load_queue(path: &Path) -> Result<Vec<Task>, Box<dyn std::error::Error>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    let tasks: Vec<Task> = serde_yaml::from_str(&contents)?;
+    Ok(tasks)
+}
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// A checkpoint of an earlier run under the task's `logs/<framework>/<experiment>/`,
+/// picked in the checkpoint browser.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResumeFrom {
+    #[serde(default)] // Older state files lack it, the env's default experiment is used then
+    experiment: String, // Experiment folder the run is in, e.g. cartpole_direct
+    load_run: String,   // Run folder name, e.g. 2025-01-31_12-00-00
+    checkpoint: String, // File in that folder, e.g. model_1500.pt
+}
+
+impl ResumeFrom {
+    pub fn new(experiment: &str, load_run: &str, checkpoint: &str) -> Self {
+        Self {
+            experiment: String::from(experiment),
+            load_run: String::from(load_run),
+            checkpoint: String::from(checkpoint),
+        }
+    }
+    pub fn get_experiment(&self) -> &str {
+        &self.experiment
+    }
+    pub fn get_load_run(&self) -> &str {
+        &self.load_run
+    }
+    pub fn get_checkpoint(&self) -> &str {
+        &self.checkpoint
+    }
+}
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ContainerRuntime {
+    Docker,
+    Apptainer,
+}
+
+impl ContainerRuntime {
+    // Also the name of the runtime's CLI
+    pub fn label(&self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Apptainer => "apptainer",
+        }
+    }
+}
+
+/// The container a task's command runs in, e.g. the official Isaac Lab image.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContainerSpec {
+    runtime: ContainerRuntime,
+    image: String,      // Docker image reference or Apptainer .sif / URI
+    flags: Vec<String>, // Extra runtime flags, e.g. `--gpus all` or `--nv`
+}
+
+impl ContainerSpec {
+    pub fn new(runtime: ContainerRuntime, image: String, flags: Vec<String>) -> Self {
+        Self {
+            runtime,
+            image,
+            flags,
+        }
+    }
+
+    /// Parses the popup's `<docker|apptainer>:<image> [flags...]` format,
+    /// e.g. `docker:nvcr.io/nvidia/isaac-lab:2.1.0 --gpus all`. Empty means none.
+    pub fn parse(spec: &str) -> Result<Option<Self>, String> {
+        let spec = spec.trim();
+        if spec.is_empty() {
+            return Ok(None);
+        }
+        let Some((kind, rest)) = spec.split_once(':') else {
+            return Err(format!(
+                "Container '{}' needs a runtime (docker:<image> or apptainer:<image>)",
+                spec
+            ));
+        };
+        let runtime = match kind.to_ascii_lowercase().as_str() {
+            "docker" => ContainerRuntime::Docker,
+            "apptainer" => ContainerRuntime::Apptainer,
+            _ => return Err(format!("Unknown container runtime '{}'", kind)),
+        };
+        let mut parts = rest.split_whitespace();
+        let Some(image) = parts.next() else {
+            return Err(String::from("Container image is missing"));
+        };
+        let flags = parts.map(String::from).collect();
+        Ok(Some(Self::new(runtime, image.to_string(), flags)))
+    }
+
+    pub fn label(&self) -> String {
+        format!("{} {}", self.runtime.label(), self.image)
+    }
+
+    pub fn get_runtime(&self) -> ContainerRuntime {
+        self.runtime
+    }
+    pub fn get_image(&self) -> &str {
+        &self.image
+    }
+    pub fn get_flags(&self) -> &[String] {
+        &self.flags
+    }
+}
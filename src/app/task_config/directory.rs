@@ -0,0 +1,92 @@
+use crate::runner::ISAACLAB_LAUNCHER;
+use crate::runner::executor::Executor;
+use std::env;
+use std::path::{self, Path, PathBuf};
+
+/// Expands a leading `~`, `$VAR` / `${VAR}` references and relative paths
+/// (against the current directory) into an absolute path.
+pub fn expand_path(text: &str) -> Result<PathBuf, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(String::from("Directory is required"));
+    }
+    let expanded = expand_vars(&expand_home(text)?)?;
+    path::absolute(&expanded).map_err(|e| format!("Can't resolve '{}': {}", expanded, e))
+}
+
+fn expand_home(text: &str) -> Result<String, String> {
+    // `~./` shows up in older saved configs and means the same as `~/`
+    let rest = match text {
+        "~" => "",
+        _ => match text.strip_prefix("~/").or_else(|| text.strip_prefix("~./")) {
+            Some(rest) => rest,
+            None => return Ok(text.to_string()),
+        },
+    };
+    let home = env::var("HOME").map_err(|_| String::from("Can't expand '~', HOME is not set"))?;
+    Ok(Path::new(&home).join(rest).to_string_lossy().to_string())
+}
+
+fn expand_vars(text: &str) -> Result<String, String> {
+    let mut expanded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let (name, remaining) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => return Err(format!("Unclosed '${{' in '{}'", text)),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        if name.is_empty() {
+            expanded.push('$'); // A lone `$` is just a character
+        } else {
+            let value = env::var(name)
+                .map_err(|_| format!("Environment variable '{}' is not set", name))?;
+            expanded.push_str(&value);
+        }
+        rest = remaining;
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Checks that `path` is a directory with Isaac Lab's launcher in it.
+pub fn validate_isaaclab_dir(path: &Path) -> Result<(), String> {
+    if !path.is_dir() {
+        return Err(format!("Directory '{}' does not exist", path.display()));
+    }
+    let launcher = path.join(ISAACLAB_LAUNCHER.trim_start_matches("./"));
+    if !launcher.is_file() {
+        return Err(format!(
+            "'{}' is not an Isaac Lab checkout (no {})",
+            path.display(),
+            ISAACLAB_LAUNCHER.trim_start_matches("./")
+        ));
+    }
+    Ok(())
+}
+
+/// Checks for Isaac Lab's launcher in `dir` on the machine the executor runs
+/// tasks on, e.g. over SSH. Not being able to check is an error of its own.
+pub fn validate_isaaclab_dir_on(executor: &dyn Executor, dir: &str) -> Result<(), String> {
+    let path = executor.resolve_dir(Path::new(dir.trim()));
+    let launcher_name = ISAACLAB_LAUNCHER.trim_start_matches("./");
+    match executor.config_exists(&path.join(launcher_name)) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!(
+            "'{}' on {} is not an Isaac Lab checkout (no {})",
+            path.display(),
+            executor.label(),
+            launcher_name
+        )),
+        Err(e) => Err(format!("Could not check '{}': {}", path.display(), e)),
+    }
+}
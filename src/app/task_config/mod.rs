@@ -1,22 +1,319 @@
-use serde_yaml::{Value, from_str};
+pub mod checkpoint;
+pub mod container;
+pub mod directory;
+pub mod retry;
+pub mod wandb;
+use checkpoint::ResumeFrom;
+use container::ContainerSpec;
+use retry::RetryPolicy;
+use serde::{Deserialize, Serialize};
+use serde_yaml::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use wandb::WandbConfig;
 
-#[derive(Debug)]
+pub const DEFAULT_TRAIN_SCRIPT: &str = "scripts/reinforcement_learning/rsl_rl/train.py";
+pub const DEFAULT_CONFIG_FILE: &str = "config.yaml";
+// Play tasks run this script from the training script's folder, e.g. rsl_rl/play.py
+pub const PLAY_SCRIPT_NAME: &str = "play.py";
+
+/// Where a task is in its lifecycle, reported back by the worker that ran it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TaskStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed(i32), // Exit code, 128 + signal when killed, -1 if it never started
+    Cancelled,
+    TimedOut,
+    Skipped, // Never ran because a task it depends on didn't succeed
+}
+
+impl TaskStatus {
+    pub fn label(&self) -> String {
+        match self {
+            TaskStatus::Queued => String::from("Queued"),
+            TaskStatus::Running => String::from("Running"),
+            TaskStatus::Succeeded => String::from("Succeeded"),
+            TaskStatus::Failed(code) => format!("Failed({})", code),
+            TaskStatus::Cancelled => String::from("Cancelled"),
+            TaskStatus::TimedOut => String::from("Timed out"),
+            TaskStatus::Skipped => String::from("Skipped"),
+        }
+    }
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, TaskStatus::Queued | TaskStatus::Running)
+    }
+}
+
+/// Where a task runs, picked per task in the create popup.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum TaskLocation {
+    #[default]
+    Local,
+    Ssh(String),   // Host name as understood by `ssh`, e.g. an entry in ~/.ssh/config
+    Slurm(String), // Extra sbatch options, e.g. `--partition=gpu --gres=gpu:1`
+}
+
+impl TaskLocation {
+    /// Parses `local` (or nothing), `ssh:<host>` and `slurm[:<sbatch options>]`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if text.is_empty() || text.eq_ignore_ascii_case("local") {
+            return Ok(TaskLocation::Local);
+        }
+        if text.eq_ignore_ascii_case("slurm") {
+            return Ok(TaskLocation::Slurm(String::new()));
+        }
+        match text.split_once(':') {
+            Some((kind, host)) if kind.eq_ignore_ascii_case("ssh") && !host.trim().is_empty() => {
+                Ok(TaskLocation::Ssh(host.trim().to_string()))
+            }
+            Some((kind, options)) if kind.eq_ignore_ascii_case("slurm") => {
+                Ok(TaskLocation::Slurm(options.trim().to_string()))
+            }
+            _ => Err(format!(
+                "Unknown location '{}' (use local, ssh:<host> or slurm[:<options>])",
+                text
+            )),
+        }
+    }
+    pub fn label(&self) -> String {
+        match self {
+            TaskLocation::Local => String::from("LOCAL"),
+            TaskLocation::Ssh(host) => format!("SSH {}", host),
+            TaskLocation::Slurm(options) if options.is_empty() => String::from("SLURM"),
+            TaskLocation::Slurm(options) => format!("SLURM {}", options),
+        }
+    }
+}
+
+/// How the YAML edited in the create popup reaches the run.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ConfigMode {
+    #[default]
+    File, // Write it over the task's config file and point Hydra at that
+    Overrides, // Pass only the changed keys as `key=value` overrides, leaving the file alone
+}
+
+impl ConfigMode {
+    /// Parses `file` (or nothing) and `overrides`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        if text.is_empty() || text.eq_ignore_ascii_case("file") {
+            Ok(ConfigMode::File)
+        } else if text.eq_ignore_ascii_case("overrides") {
+            Ok(ConfigMode::Overrides)
+        } else {
+            Err(format!(
+                "Unknown config mode '{}' (use file or overrides)",
+                text
+            ))
+        }
+    }
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConfigMode::File => "file",
+            ConfigMode::Overrides => "overrides",
+        }
+    }
+}
+
+/// What a task runs: training, or an evaluation of a trained policy.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum TaskKind {
+    #[default]
+    Train,
+    Play {
+        checkpoint: String, // Relative to the task's directory, or absolute
+        video: bool,        // Record a clip with `--video` instead of only running the policy
+    },
+}
+
+impl TaskKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TaskKind::Train => "train",
+            TaskKind::Play { video: false, .. } => "play",
+            TaskKind::Play { video: true, .. } => "play+video",
+        }
+    }
+}
+
+/// Python environment activated before the Isaac Lab command runs, so
+/// `isaaclab.sh` picks up that environment's interpreter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PythonEnv {
+    Conda(String), // Env name (or prefix path) as understood by `conda activate`
+    Venv(String),  // Directory containing bin/activate
+}
+
+impl PythonEnv {
+    /// Parses `conda:<name>` and `venv:<path>`. Empty means none.
+    pub fn parse(text: &str) -> Result<Option<Self>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        match text.split_once(':') {
+            Some((kind, name)) if kind.eq_ignore_ascii_case("conda") && !name.trim().is_empty() => {
+                Ok(Some(PythonEnv::Conda(name.trim().to_string())))
+            }
+            Some((kind, path)) if kind.eq_ignore_ascii_case("venv") && !path.trim().is_empty() => {
+                Ok(Some(PythonEnv::Venv(path.trim().to_string())))
+            }
+            _ => Err(format!(
+                "Unknown Python env '{}' (use conda:<name> or venv:<path>)",
+                text
+            )),
+        }
+    }
+    pub fn label(&self) -> String {
+        match self {
+            PythonEnv::Conda(name) => format!("conda {}", name),
+            PythonEnv::Venv(path) => format!("venv {}", path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)] // Older state files may lack newer fields
 pub struct Task {
+    id: usize, // Assigned by the App when queued, unique for the session
+    status: TaskStatus,
     task_name: String,
     environment: String,
     directory: String,
+    location: TaskLocation,
+    container: Option<ContainerSpec>, // Runs the command in this container when set
+    env_vars: BTreeMap<String, String>, // Extra environment for the run, e.g. WANDB_PROJECT
+    python_env: Option<PythonEnv>,    // Activated first, otherwise the launching shell's python
+    wandb: Option<WandbConfig>,       // Log to Weights & Biases when set
+    wandb_url: Option<String>,        // W&B page of the latest run, taken from its output
+    script: String,                   // Training script, relative to the Isaac Lab directory
+    config_file: String,              // Where the edited YAML is written, relative to the directory
     yaml: Option<Value>,
+    config_mode: ConfigMode,
+    retry: Option<RetryPolicy>,
+    timeout: Option<Duration>,  // Wall-clock limit for a single run
+    dependencies: Vec<usize>,   // Ids of tasks that must succeed before this one starts
+    attempts: u32,              // Runs started so far
+    run_dir: Option<String>,    // Snapshot directory of the latest run
+    resume: Option<ResumeFrom>, // Continue training from this checkpoint
+    kind: TaskKind,
+    #[serde(skip)]
+    not_before: Option<Instant>, // Earliest time a retried task may start again
+}
+
+impl Default for Task {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Task {
     pub fn new() -> Self {
         Self {
+            id: 0,
+            status: TaskStatus::Queued,
             task_name: String::from(""),
             environment: String::from(""),
             directory: String::from(""),
+            location: TaskLocation::Local,
+            container: None,
+            env_vars: BTreeMap::new(),
+            python_env: None,
+            wandb: None,
+            wandb_url: None,
+            script: String::from(DEFAULT_TRAIN_SCRIPT),
+            config_file: String::from(DEFAULT_CONFIG_FILE),
             yaml: None,
+            config_mode: ConfigMode::File,
+            retry: None,
+            timeout: None,
+            dependencies: vec![],
+            attempts: 0,
+            run_dir: None,
+            resume: None,
+            kind: TaskKind::Train,
+            not_before: None,
         }
     }
+    pub fn get_id(&self) -> usize {
+        self.id
+    }
+    pub fn set_id(&mut self, id: usize) {
+        self.id = id;
+    }
+    pub fn get_status(&self) -> &TaskStatus {
+        &self.status
+    }
+    pub fn set_status(&mut self, status: TaskStatus) {
+        self.status = status;
+    }
+    pub fn get_retry(&self) -> &Option<RetryPolicy> {
+        &self.retry
+    }
+    pub fn set_retry(&mut self, retry: Option<RetryPolicy>) {
+        self.retry = retry;
+    }
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+    pub fn get_dependencies(&self) -> &[usize] {
+        &self.dependencies
+    }
+    pub fn set_dependencies(&mut self, dependencies: Vec<usize>) {
+        self.dependencies = dependencies;
+    }
+    pub fn get_attempts(&self) -> u32 {
+        self.attempts
+    }
+    pub fn set_attempts(&mut self, attempts: u32) {
+        self.attempts = attempts;
+    }
+    pub fn get_run_dir(&self) -> &Option<String> {
+        &self.run_dir
+    }
+    pub fn set_run_dir(&mut self, run_dir: Option<String>) {
+        self.run_dir = run_dir;
+    }
+    pub fn get_resume(&self) -> &Option<ResumeFrom> {
+        &self.resume
+    }
+    pub fn set_resume(&mut self, resume: Option<ResumeFrom>) {
+        self.resume = resume;
+    }
+    pub fn get_kind(&self) -> &TaskKind {
+        &self.kind
+    }
+    pub fn set_kind(&mut self, kind: TaskKind) {
+        self.kind = kind;
+    }
+    /// The script the task runs: its training script, or the play script next to it.
+    pub fn get_launch_script(&self) -> String {
+        match self.kind {
+            TaskKind::Train => self.script.clone(),
+            TaskKind::Play { .. } => Path::new(&self.script)
+                .with_file_name(PLAY_SCRIPT_NAME)
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+    pub fn get_not_before(&self) -> Option<Instant> {
+        self.not_before
+    }
+    pub fn set_not_before(&mut self, not_before: Option<Instant>) {
+        self.not_before = not_before;
+    }
+    // Queued and, if it is waiting out a retry delay, done waiting
+    pub fn is_ready(&self, now: Instant) -> bool {
+        self.status == TaskStatus::Queued && self.not_before.is_none_or(|time| time <= now)
+    }
     pub fn get_yaml(&self) -> &Option<Value> {
         &self.yaml
     }
@@ -32,6 +329,63 @@ impl Task {
     pub fn get_directory(&self) -> &str {
         &self.directory
     }
+    pub fn get_location(&self) -> &TaskLocation {
+        &self.location
+    }
+    pub fn set_location(&mut self, location: TaskLocation) {
+        self.location = location;
+    }
+    pub fn get_container(&self) -> &Option<ContainerSpec> {
+        &self.container
+    }
+    pub fn set_container(&mut self, container: Option<ContainerSpec>) {
+        self.container = container;
+    }
+    pub fn get_env_vars(&self) -> &BTreeMap<String, String> {
+        &self.env_vars
+    }
+    pub fn set_env_vars(&mut self, env_vars: BTreeMap<String, String>) {
+        self.env_vars = env_vars;
+    }
+    pub fn get_wandb(&self) -> &Option<WandbConfig> {
+        &self.wandb
+    }
+    pub fn set_wandb(&mut self, wandb: Option<WandbConfig>) {
+        self.wandb = wandb;
+    }
+    pub fn get_wandb_url(&self) -> &Option<String> {
+        &self.wandb_url
+    }
+    pub fn set_wandb_url(&mut self, wandb_url: Option<String>) {
+        self.wandb_url = wandb_url;
+    }
+    pub fn get_config_mode(&self) -> ConfigMode {
+        self.config_mode
+    }
+    pub fn set_config_mode(&mut self, config_mode: ConfigMode) {
+        self.config_mode = config_mode;
+    }
+    pub fn get_python_env(&self) -> &Option<PythonEnv> {
+        &self.python_env
+    }
+    pub fn set_python_env(&mut self, python_env: Option<PythonEnv>) {
+        self.python_env = python_env;
+    }
+    pub fn get_script(&self) -> &str {
+        &self.script
+    }
+    pub fn get_config_file(&self) -> &str {
+        &self.config_file
+    }
+    pub fn task_name_mut(&mut self) -> &mut String {
+        &mut self.task_name
+    }
+    pub fn environment_mut(&mut self) -> &mut String {
+        &mut self.environment
+    }
+    pub fn directory_mut(&mut self) -> &mut String {
+        &mut self.directory
+    }
     pub fn set_task_name(&mut self, string: String) {
         self.task_name = string;
     }
@@ -41,4 +395,80 @@ impl Task {
     pub fn set_directory(&mut self, string: String) {
         self.directory = string;
     }
+    pub fn set_script(&mut self, string: String) {
+        self.script = string;
+    }
+    pub fn set_config_file(&mut self, string: String) {
+        self.config_file = string;
+    }
+}
+
+// Longest timeout taken; a year is plenty and stays well inside what `Instant` can hold
+const MAX_TIMEOUT_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// Parses durations like `3600`, `90s`, `30m`, `12h` or `2d`. Empty means none.
+pub fn parse_duration(text: &str) -> Result<Option<Duration>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let (number, unit_secs) = match text.char_indices().last() {
+        Some((i, 's')) => (&text[..i], 1),
+        Some((i, 'm')) => (&text[..i], 60),
+        Some((i, 'h')) => (&text[..i], 60 * 60),
+        Some((i, 'd')) => (&text[..i], 24 * 60 * 60),
+        _ => (text, 1),
+    };
+    match number.trim().parse::<u64>() {
+        Ok(0) => Err(String::from("Timeout must be longer than 0")),
+        Ok(n) => match n.checked_mul(unit_secs) {
+            Some(secs) if secs <= MAX_TIMEOUT_SECS => Ok(Some(Duration::from_secs(secs))),
+            _ => Err(String::from("Timeout can be at most a year")),
+        },
+        Err(_) => Err(format!("'{}' is not a duration (e.g. 90m, 12h)", text)),
+    }
+}
+
+/// Parses space separated `KEY=value` pairs, e.g.
+/// `CUDA_VISIBLE_DEVICES=0 MSG="two words"`. Values may be quoted the way a
+/// shell would, with `"` or `'`, to hold spaces. Empty means none.
+pub fn parse_env_vars(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut env_vars = BTreeMap::new();
+    for pair in split_quoted(text)? {
+        let Some((key, value)) = pair.split_once('=') else {
+            return Err(format!("'{}' is not KEY=value", pair));
+        };
+        let is_valid_key = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_valid_key {
+            return Err(format!("'{}' is not a valid variable name", key));
+        }
+        env_vars.insert(key.to_string(), value.to_string());
+    }
+    Ok(env_vars)
+}
+
+// Whitespace separated words, with the quotes around quoted parts removed
+fn split_quoted(text: &str) -> Result<Vec<String>, String> {
+    let mut words = vec![];
+    let mut word: Option<String> = None; // None between words
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' | '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some(inner) => word.push(inner),
+                        None => return Err(format!("Missing closing {} in '{}'", c, text)),
+                    }
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
 }
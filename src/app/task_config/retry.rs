@@ -0,0 +1,109 @@
+use super::TaskStatus;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+// Longest wait between attempts; anything more is a typo, and would overflow `Instant`
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// When and how often a failed task is run again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    max_attempts: u32,         // Total runs, including the first one
+    delay: Duration,           // Wait between a failure and the next attempt
+    retryable_codes: Vec<i32>, // Exit codes worth retrying, empty means any failure
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, delay: Duration, retryable_codes: Vec<i32>) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            delay,
+            retryable_codes,
+        }
+    }
+
+    /// Parses the popup's `<attempts> [delay secs] [code,code,...]` format,
+    /// e.g. `3 30 1,134`. An empty spec means no retries.
+    pub fn parse(spec: &str) -> Result<Option<Self>, String> {
+        let mut parts = spec.split_whitespace();
+        let Some(attempts) = parts.next() else {
+            return Ok(None);
+        };
+        let max_attempts: u32 = attempts
+            .parse()
+            .map_err(|_| format!("Retry attempts '{}' is not a number", attempts))?;
+        if max_attempts == 0 {
+            return Err(String::from("Retry attempts must be at least 1"));
+        }
+
+        let delay = match parts.next() {
+            Some(secs) => Duration::from_secs(
+                secs.parse()
+                    .map_err(|_| format!("Retry delay '{}' is not a number of seconds", secs))?,
+            ),
+            None => Duration::ZERO,
+        };
+        if delay > MAX_RETRY_DELAY {
+            return Err(format!(
+                "Retry delay can be at most {}s (a week)",
+                MAX_RETRY_DELAY.as_secs()
+            ));
+        }
+
+        let mut retryable_codes = vec![];
+        if let Some(codes) = parts.next() {
+            for code in codes.split(',').filter(|code| !code.is_empty()) {
+                retryable_codes.push(
+                    code.parse()
+                        .map_err(|_| format!("Retry exit code '{}' is not a number", code))?,
+                );
+            }
+        }
+        if let Some(extra) = parts.next() {
+            return Err(format!("Unexpected '{}' in retry settings", extra));
+        }
+
+        Ok(Some(Self::new(max_attempts, delay, retryable_codes)))
+    }
+
+    // Back to the popup format, so an existing policy can be shown and edited
+    pub fn to_spec(&self) -> String {
+        let codes: Vec<String> = self
+            .retryable_codes
+            .iter()
+            .map(|code| code.to_string())
+            .collect();
+        format!(
+            "{} {} {}",
+            self.max_attempts,
+            self.delay.as_secs(),
+            codes.join(",")
+        )
+        .trim_end()
+        .to_string()
+    }
+
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    pub fn get_delay(&self) -> Duration {
+        self.delay
+    }
+    pub fn get_retryable_codes(&self) -> &[i32] {
+        &self.retryable_codes
+    }
+
+    /// Whether a task that ended with `status` after `attempts` runs should go again.
+    pub fn should_retry(&self, attempts: u32, status: &TaskStatus) -> bool {
+        if attempts >= self.max_attempts {
+            return false;
+        }
+        // Cancelled or successful runs are never retried
+        match status {
+            TaskStatus::Failed(code) => {
+                self.retryable_codes.is_empty() || self.retryable_codes.contains(code)
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// Weights & Biases logging for a task's runs. Unset fields fall back to
+/// whatever the train script and the user's W&B settings default to.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct WandbConfig {
+    entity: Option<String>,
+    project: Option<String>,
+}
+
+impl WandbConfig {
+    pub fn new(entity: Option<String>, project: Option<String>) -> Self {
+        Self { entity, project }
+    }
+
+    /// Parses the popup's `on`, `<project>` or `<entity>/<project>`.
+    /// Empty or `off` means W&B is not used.
+    pub fn parse(text: &str) -> Result<Option<Self>, String> {
+        let text = text.trim();
+        if text.is_empty() || text.eq_ignore_ascii_case("off") {
+            return Ok(None);
+        }
+        if text.eq_ignore_ascii_case("on") {
+            return Ok(Some(Self::default()));
+        }
+        if text.contains(char::is_whitespace) {
+            return Err(format!("W&B project '{}' can't contain spaces", text));
+        }
+        match text.split_once('/') {
+            Some((entity, project)) if !entity.is_empty() && !project.is_empty() => Ok(Some(
+                Self::new(Some(entity.to_string()), Some(project.to_string())),
+            )),
+            Some(_) => Err(format!("'{}' is not <entity>/<project>", text)),
+            None => Ok(Some(Self::new(None, Some(text.to_string())))),
+        }
+    }
+
+    pub fn get_entity(&self) -> Option<&str> {
+        self.entity.as_deref()
+    }
+    pub fn get_project(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+}
@@ -1,5 +1,5 @@
-use crossterm::event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode};
-use std::{error, io::Error, time::Duration};
+use crossterm::event::{self, Event, KeyCode};
+use std::time::Duration;
 pub enum Actions {
     Quit,
     Enter,
@@ -12,13 +12,21 @@ pub enum Actions {
     Right,
     None,
     Delete,
+    ScrollUp,
+    ScrollDown,
+    Cancel,
+    History,
+    Scalars,
+    Checkpoints,
+    Play(bool), // Record video while playing
+    ClearFinished,
 }
 
 pub fn handle_key_input(timeout: Duration, read_key_stroke: bool) -> Option<Actions> {
     if read_key_stroke {
-        return read_key_strokes(timeout);
+        read_key_strokes(timeout)
     } else {
-        return traverse_with_keys(timeout);
+        traverse_with_keys(timeout)
     }
 }
 pub fn traverse_with_keys(timeout: Duration) -> Option<Actions> {
@@ -28,12 +36,21 @@ pub fn traverse_with_keys(timeout: Duration) -> Option<Actions> {
             match key.code {
                 KeyCode::Esc => return Some(Actions::Quit), // Quit if 'q' is pressed
                 KeyCode::Char('c') => return Some(Actions::Createtask), // Quit if 'q' is pressed
+                KeyCode::Char('x') => return Some(Actions::Cancel), // Stop the running task
+                KeyCode::Char('h') => return Some(Actions::History), // Browse past runs
+                KeyCode::Char('t') => return Some(Actions::Scalars), // Plot TensorBoard scalars
+                KeyCode::Char('r') => return Some(Actions::Checkpoints), // Resume from a checkpoint
+                KeyCode::Char('p') => return Some(Actions::Play(false)), // Evaluate a checkpoint
+                KeyCode::Char('v') => return Some(Actions::Play(true)), // Same, recording video
+                KeyCode::Char('d') => return Some(Actions::ClearFinished), // Drop ended tasks
                 KeyCode::Up => return Some(Actions::Moveup),
                 KeyCode::Down => return Some(Actions::Movedown),
                 KeyCode::Left => return Some(Actions::Left),
                 KeyCode::Right => return Some(Actions::Right),
                 KeyCode::Tab => return Some(Actions::Tab),
                 KeyCode::Enter => return Some(Actions::Enter),
+                KeyCode::PageUp => return Some(Actions::ScrollUp),
+                KeyCode::PageDown => return Some(Actions::ScrollDown),
                 _ => {} // Handle other keys if needed (e.g., return None for non-'q' keys)
             }
         } else {
@@ -58,6 +75,8 @@ pub fn read_key_strokes(timeout: Duration) -> Option<Actions> {
                 KeyCode::Esc => return Some(Actions::Quit),
                 KeyCode::Backspace => return Some(Actions::Delete),
                 KeyCode::Tab => return Some(Actions::Tab),
+                KeyCode::PageUp => return Some(Actions::ScrollUp),
+                KeyCode::PageDown => return Some(Actions::ScrollDown),
                 _ => {} // Handle other keys if needed (e.g., return None for non-'q' keys)
             }
         } else {
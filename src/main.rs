@@ -1,32 +1,19 @@
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event as CEvent},
+    event::{DisableMouseCapture, EnableMouseCapture},
     execute,
-    terminal::{
-        self, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode,
-    },
-};
-use ratatui::{
-    Terminal,
-    backend::{self, CrosstermBackend},
-    layout::{Constraint, Direction, Layout},
-    prelude::Backend,
-    text,
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
-use std::thread;
+use ratatui::{Terminal, backend::CrosstermBackend, prelude::Backend};
+use serde::Serialize;
+use serde_yaml::Value;
+use std::os::unix::process::ExitStatusExt;
 use std::{
-    fs::File,
-    io::{self, Read, Write},
-    panic,
-    path::Path,
-    ptr::null,
-    time::{Duration, Instant},
-};
-use std::{
-    process::{Command, exit},
-    thread::JoinHandle,
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::Duration,
 };
 // mod app;
 pub mod events;
@@ -34,99 +21,206 @@ use events::{Actions, handle_key_input};
 pub mod uis;
 use uis::{Mainpage, render_page};
 pub mod app;
-use app::{App, State};
+use app::{
+    App,
+    history::{self, RunRecord},
+    storage,
+    task_config::{ConfigMode, Task, TaskLocation, TaskStatus, checkpoint::ResumeFrom, directory},
+};
+pub mod runner;
+use runner::{
+    LaunchCommand,
+    backup::{ConfigBackups, SharedBackups},
+    checkpoints::{self, CheckpointRun},
+    control::{RunControl, SharedControl},
+    executor::{self, Executor},
+    log_buffer::{self, SharedLog},
+    pool::WorkerPool,
+    tensorboard::{self, EventRun, ScalarSeries},
+};
 // use event::{Event, EventHandler};
+
+const LOG_SCROLL_STEP: usize = 10; // Lines moved per PageUp/PageDown
 //
 
 fn main() {
     let mut app = App::new_app();
+    if let Some(max_workers) = parse_workers_arg(std::env::args()) {
+        app.set_max_workers(max_workers);
+    }
+    // Leave whatever the last task wrote in its config file instead of the original
+    if std::env::args().any(|arg| arg == "--no-restore-config") {
+        app.set_restore_config(false);
+    }
 
     // Stdout is the output of the termianl and if used io::stdout().flush() all entries in terminal
     // buffer are flushed into termianl for display. execture handles event calles and flushes
-    execute!(io::stdout(), EnterAlternateScreen, DisableMouseCapture);
-    enable_raw_mode();
+    let _ = execute!(io::stdout(), EnterAlternateScreen, DisableMouseCapture);
+    let _ = enable_raw_mode();
 
     let backend = CrosstermBackend::new(io::stdout()); // Creates backend for abstract terminal communication
     // Includes methods like size, clear, cursor pos, ...
-    let mut terminal = Terminal::new(backend);
+    let terminal = Terminal::new(backend);
     match terminal {
         Ok(mut term) => run_app(&mut term, &mut app),
         _ => eprintln!("Error init terminal..."),
     }
 
-    disable_raw_mode();
-    execute!(io::stdout(), LeaveAlternateScreen, EnableMouseCapture);
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, EnableMouseCapture);
 
     println!("->{:?}", app.task_template_task());
 
     println!("Finished");
 }
 
+// `--workers N` sets how many queued tasks may run at the same time
+fn parse_workers_arg<I: Iterator<Item = String>>(mut args: I) -> Option<usize> {
+    while let Some(arg) = args.next() {
+        if arg == "--workers" || arg == "-j" {
+            return args.next().and_then(|n| n.parse().ok());
+        }
+        if let Some(n) = arg.strip_prefix("--workers=") {
+            return n.parse().ok();
+        }
+    }
+    None
+}
+
 // Main app loop function using handle_key_input
 fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) {
-    // Shared status between main thread and worker thread
-    let status = Arc::new(Mutex::new(true));
-    let mut thread_handle: Option<JoinHandle<()>> = None;
+    // Each worker slot holds its own status, filled in by the worker when its task ends
+    let mut pool = WorkerPool::new(app.get_max_workers());
+    let backups = app
+        .get_restore_config()
+        .then(|| ConfigBackups::new_shared(storage::backups_dir()));
+
+    // Lookups that may go over SSH report back here instead of blocking the UI
+    let (lookups, finished_lookups) = mpsc::channel();
 
     let mut mp_struct = Mainpage::new();
     app.set_state(app::State::Main);
     while *app.get_state() == app::State::Main {
         if *mp_struct.get_create_window() {
-            task_creating(&mut mp_struct, app);
+            task_creating(&mut mp_struct, app, &lookups);
+        } else if *mp_struct.get_history_mode() {
+            history_browsing(&mut mp_struct);
+        } else if *mp_struct.get_scalars_mode() {
+            scalars_browsing(&mut mp_struct, app, &lookups);
+        } else if *mp_struct.get_checkpoints_mode() {
+            checkpoints_browsing(&mut mp_struct, app);
         } else {
-            task_browsing(&mut mp_struct, app);
+            task_browsing(&mut mp_struct, app, &lookups);
+        }
+        apply_finished_lookups(&mut mp_struct, &finished_lookups);
+
+        // Show the selected task's output, or the most recently started one
+        let selected_log = app
+            .get_task_at(mp_struct.get_selected_task_index())
+            .and_then(|task| {
+                let log = app.get_task_log(task.get_id())?;
+                Some((task.get_id(), task.get_task_name(), log))
+            })
+            .or_else(|| app.get_latest_log());
+        if let Some((task_id, name, log)) = selected_log {
+            mp_struct.set_task_log(task_id, name, Some(SharedLog::clone(log)));
         }
 
         // Render UI in a separate function
         render_page(terminal, &mut mp_struct);
 
-        let mut done = status.lock().unwrap();
+        // Record the outcome of finished tasks before picking the next ones
+        record_finished(&mut pool, app);
 
-        if *done {
-            // Update mp_struct with new data
-            mp_struct.update_task_list(app.get_task_queue_names());
+        // Fill every free slot from the queue
+        while pool.has_free_slot()
+            && let Some(mut task) = app.pop_first_task()
+        {
+            let log = log_buffer::new_shared_log();
+            app.add_task_log(&task, SharedLog::clone(&log));
 
-            if thread_handle.is_none() && !app.task_queue_is_empty() {
-                if let Some(task) = app.pop_first_task() {
-                    *done = false; // Reset status
-                    match write_yaml(task.get_directory(), task.get_yaml()) {
-                        Ok(_) => {
-                            let command = "echo test >> text.txt; sleep 10";
-                            let status_clone = Arc::clone(&status);
-
-                            thread_handle = Some(thread::spawn(move || {
-                                run_bash_command(command);
-                                // println!("Here");
-                                let mut done = status_clone.lock().unwrap();
-                                *done = true;
-                            }));
-                        }
-                        Err(e) => {
-                            eprintln!("Failed to write YAML file: {:?}", e);
-                        }
-                    }
-                }
-            } else {
-                // Thread is done, just clear the handle — no join
-                thread_handle = None;
-                // println!("Thread finished, ready for next process.");
+            // Keep the full output on disk, in the run's own directory
+            let started_at = history::now_unix_secs();
+            let run_dir = history::run_dir_for(&task, started_at);
+            let run_dir_label = run_dir.display().to_string();
+            task.set_run_dir(Some(run_dir_label.clone()));
+            app.set_task_run_dir(task.get_id(), run_dir_label);
+            let log_path = run_dir.join(history::RUN_LOG_FILE);
+            // Bound first: the guard would otherwise live through the `if let` body
+            let opened = log.lock().unwrap().tee_to_file(&log_path);
+            if let Err(e) = opened {
+                log.lock()
+                    .unwrap()
+                    .push_line(format!("Could not open log file: {}", e));
             }
+
+            // Only runs that write over the config file need it put back afterwards
+            let backups = backups
+                .as_ref()
+                .filter(|_| task.get_config_mode() == ConfigMode::File)
+                .map(SharedBackups::clone);
+            let control = RunControl::new_shared();
+            app.add_running_control(task.get_id(), SharedControl::clone(&control));
+            let run = TaskRun {
+                log,
+                control,
+                backups,
+                run_dir,
+                started_at,
+                log_path,
+            };
+            // Everything from here may talk to a remote host, so it stays off the UI thread
+            pool.spawn(task.get_id(), move || run.run(&task));
         }
+
+        // Update mp_struct with new data
+        mp_struct.update_task_list(app.get_task_queue_names());
+        mp_struct.update_task_statuses(app.get_task_statuses());
+        mp_struct.update_task_remote_states(app.get_task_remote_states());
+        mp_struct.set_task_running(pool.running_count() > 0);
+        if let Some(label) = app.get_task_location_label_at(mp_struct.get_selected_task_index()) {
+            mp_struct.set_task_execution_location(label);
+        }
+        app.refresh_wandb_urls();
+        let run_dir = app.get_task_run_dir_at(mp_struct.get_selected_task_index());
+        mp_struct.set_task_run_dir(run_dir.unwrap_or_default());
+        let selected_task = app.get_task_at(mp_struct.get_selected_task_index());
+        mp_struct.set_wandb(selected_task.is_some_and(|task| task.get_wandb().is_some()));
+        mp_struct.set_wandb_url(
+            selected_task
+                .and_then(|task| task.get_wandb_url().clone())
+                .unwrap_or_default(),
+        );
+        mp_struct.set_storage_error(app.get_storage_error().clone().unwrap_or_default());
+        mp_struct.set_workers(pool.running_count(), pool.get_max_workers());
     }
 
-    if let Some(handle) = thread_handle.take() {
-        match handle.join() {
-            Ok(_) => {
-                println!("Thread finished successfully.");
-            }
-            Err(e) => {
-                eprintln!("Thread panicked: {:?}", e);
-            }
+    // Running tasks keep the app open until they end, so their outcome is saved
+    // to the queue file instead of being lost as `Running`
+    mp_struct.set_quitting(true);
+    while pool.running_count() > 0 {
+        mp_struct.update_task_statuses(app.get_task_statuses());
+        mp_struct.set_workers(pool.running_count(), pool.get_max_workers());
+        render_page(terminal, &mut mp_struct);
+        if let Some(Actions::Cancel) = handle_key_input(Duration::from_millis(100), false) {
+            app.cancel_all_tasks();
         }
+        record_finished(&mut pool, app);
     }
+    pool.join_all();
 }
 
-fn task_browsing(mp_struct: &mut Mainpage, app: &mut App) {
+// Moves the outcome of every finished worker onto its task, retrying it if it may
+fn record_finished(pool: &mut WorkerPool, app: &mut App) {
+    for (task_id, outcome) in pool.collect_finished() {
+        app.remove_running_control(task_id);
+        if !app.retry_task(task_id, &outcome) {
+            app.set_task_status(task_id, outcome);
+        }
+    }
+}
+
+fn task_browsing(mp_struct: &mut Mainpage, app: &mut App, lookups: &Sender<Lookup>) {
     // Call handle_key_input with a timeout of 5 milliseconds
     match handle_key_input(Duration::from_micros(5000), false) {
         Some(Actions::Quit) => {
@@ -144,9 +238,34 @@ fn task_browsing(mp_struct: &mut Mainpage, app: &mut App) {
             mp_struct.increase_selection();
             mp_struct.set_active_view(false);
         }
+        Some(Actions::History) => match history::load_history(&history::history_path()) {
+            Ok(records) => mp_struct.open_history(records),
+            Err(e) => app.report_storage_error(format!("Could not read run history: {}", e)),
+        },
+        Some(Actions::Scalars) => open_scalars(mp_struct, app, lookups),
+        // Playing needs a checkpoint too, so both start in the checkpoint browser
+        Some(Actions::Checkpoints | Actions::Play(_)) => open_checkpoints(mp_struct, app, lookups),
+        Some(Actions::Cancel) => {
+            app.cancel_task_at(mp_struct.get_selected_task_index());
+        }
+        Some(Actions::ClearFinished) => {
+            if app.clear_finished_tasks() > 0 {
+                mp_struct.update_task_list(app.get_task_queue_names());
+                mp_struct.clamp_selection();
+            }
+        }
+        Some(Actions::ScrollUp) => {
+            mp_struct.scroll_log_up(LOG_SCROLL_STEP);
+        }
+        Some(Actions::ScrollDown) => {
+            mp_struct.scroll_log_down(LOG_SCROLL_STEP);
+        }
         Some(Actions::Enter) => {
             mp_struct.set_active_view(true);
-            let name = mp_struct.get_current_task_selection_name().to_string(); // clone String
+            let name = mp_struct
+                .get_selected_task_name()
+                .unwrap_or_default()
+                .to_string(); // clone String
             if let Some((env, dir)) = app.get_task_info(&name) {
                 mp_struct.set_temp_name(name);
                 mp_struct.set_temp_env(env);
@@ -168,7 +287,272 @@ fn task_browsing(mp_struct: &mut Mainpage, app: &mut App) {
     }
 }
 
-fn task_creating(mp_struct: &mut Mainpage, app: &mut App) {
+fn history_browsing(mp_struct: &mut Mainpage) {
+    match handle_key_input(Duration::from_micros(5000), false) {
+        Some(Actions::Quit) | Some(Actions::History) => mp_struct.close_history(),
+        Some(Actions::Moveup) => mp_struct.decrease_history_selection(),
+        Some(Actions::Movedown) => mp_struct.increase_history_selection(),
+        Some(Actions::ScrollUp) => mp_struct.scroll_history_up(LOG_SCROLL_STEP),
+        Some(Actions::ScrollDown) => mp_struct.scroll_history_down(LOG_SCROLL_STEP),
+        Some(_) => {}
+        None => {
+            eprintln!("Error reading key input.");
+        }
+    }
+}
+
+// Lists the TensorBoard runs under the selected task's logs, finished or not,
+// including ones started outside this tool, and loads the first of them
+fn open_scalars(mp_struct: &mut Mainpage, app: &App, lookups: &Sender<Lookup>) {
+    let Some(task) = app.get_task_at(mp_struct.get_selected_task_index()) else {
+        return;
+    };
+    let logs_dir = Path::new(task.get_directory()).join(runner::LOGS_DIR);
+    mp_struct.open_scalars(vec![]);
+    mp_struct.set_scalars_message(format!("Searching {}...", logs_dir.display()));
+    let task = task.clone();
+    let lookups = lookups.clone();
+    thread::spawn(move || {
+        let executor = executor::for_task(&task);
+        // As the executor lists them, e.g. with a remote `~` expanded
+        let logs_dir = logs_dir_of(&*executor, &task);
+        let runs = tensorboard::find_event_runs(&*executor, &logs_dir);
+        // The first run is the one selected when the browser opens
+        let first = match &runs {
+            Ok(runs) => runs.first().map(|run| {
+                let scalars = tensorboard::load_scalars(&*executor, run);
+                (run.clone(), scalars.map_err(|e| e.to_string()))
+            }),
+            Err(_) => None,
+        };
+        let runs = runs.map_err(|e| e.to_string());
+        let _ = lookups.send(Lookup::ScalarRuns {
+            logs_dir,
+            runs,
+            first,
+        });
+    });
+}
+
+fn load_selected_scalars(mp_struct: &mut Mainpage, task: &Task, lookups: &Sender<Lookup>) {
+    let Some(run) = mp_struct.get_selected_scalar_run().cloned() else {
+        return;
+    };
+    mp_struct.set_scalars_message(format!("Loading {}...", run.get_label()));
+    let task = task.clone();
+    let lookups = lookups.clone();
+    thread::spawn(move || {
+        let scalars = tensorboard::load_scalars(&*executor::for_task(&task), &run);
+        let scalars = scalars.map_err(|e| e.to_string());
+        let _ = lookups.send(Lookup::Scalars(run, scalars));
+    });
+}
+
+fn show_scalars(mp_struct: &mut Mainpage, run: &EventRun, scalars: LookupResult<ScalarTags>) {
+    match scalars {
+        Ok(scalars) if scalars.is_empty() => {
+            mp_struct.set_scalars(scalars);
+            mp_struct.set_scalars_message(format!("{} has no scalars yet", run.get_label()));
+        }
+        Ok(scalars) => {
+            mp_struct.set_scalars(scalars);
+            mp_struct.set_scalars_message("");
+            mp_struct.set_scalar_tags_focused(true);
+        }
+        Err(e) => {
+            mp_struct.set_scalars(Default::default());
+            mp_struct.set_scalars_message(format!("Could not read {}: {}", run.get_label(), e));
+        }
+    }
+}
+
+fn scalars_browsing(mp_struct: &mut Mainpage, app: &App, lookups: &Sender<Lookup>) {
+    match handle_key_input(Duration::from_micros(5000), false) {
+        Some(Actions::Quit) | Some(Actions::Scalars) => mp_struct.close_scalars(),
+        Some(Actions::Moveup) => mp_struct.decrease_scalar_selection(),
+        Some(Actions::Movedown) => mp_struct.increase_scalar_selection(),
+        Some(Actions::Tab) => {
+            let tags_focused = *mp_struct.get_scalar_tags_focused();
+            mp_struct.set_scalar_tags_focused(!tags_focused);
+        }
+        // Also reloads, to pick up what a running task has written since
+        Some(Actions::Enter) => {
+            if let Some(task) = app.get_task_at(mp_struct.get_selected_task_index()) {
+                load_selected_scalars(mp_struct, task, lookups);
+            }
+        }
+        Some(_) => {}
+        None => {
+            eprintln!("Error reading key input.");
+        }
+    }
+}
+
+// Lists the saved checkpoints under the selected task's logs, per run
+fn open_checkpoints(mp_struct: &mut Mainpage, app: &App, lookups: &Sender<Lookup>) {
+    let Some(task) = app.get_task_at(mp_struct.get_selected_task_index()) else {
+        return;
+    };
+    let logs_dir = Path::new(task.get_directory()).join(runner::LOGS_DIR);
+    mp_struct.open_checkpoints(vec![]);
+    mp_struct.set_checkpoints_message(format!("Searching {}...", logs_dir.display()));
+    let task = task.clone();
+    let lookups = lookups.clone();
+    thread::spawn(move || {
+        let executor = executor::for_task(&task);
+        let logs_dir = logs_dir_of(&*executor, &task);
+        let runs = checkpoints::find_checkpoint_runs(&*executor, &logs_dir);
+        let runs = runs.map_err(|e| e.to_string());
+        let _ = lookups.send(Lookup::CheckpointRuns { logs_dir, runs });
+    });
+}
+
+// The task's logs directory, absolute so the paths found under it can be made relative to it
+fn logs_dir_of(executor: &dyn Executor, task: &Task) -> PathBuf {
+    executor
+        .resolve_dir(Path::new(task.get_directory()))
+        .join(runner::LOGS_DIR)
+}
+
+type LookupResult<T> = Result<T, String>;
+type ScalarTags = BTreeMap<String, ScalarSeries>;
+
+// What a background lookup found, for the page that asked for it
+enum Lookup {
+    ScalarRuns {
+        logs_dir: PathBuf,
+        runs: LookupResult<Vec<EventRun>>,
+        first: Option<(EventRun, LookupResult<ScalarTags>)>,
+    },
+    Scalars(EventRun, LookupResult<ScalarTags>),
+    CheckpointRuns {
+        logs_dir: PathBuf,
+        runs: LookupResult<Vec<CheckpointRun>>,
+    },
+    ConfigDiff(LookupResult<Vec<String>>), // Err when the task's directory is no good
+}
+
+// Shows what finished lookups found, unless their page was closed meanwhile
+fn apply_finished_lookups(mp_struct: &mut Mainpage, finished: &Receiver<Lookup>) {
+    while let Ok(lookup) = finished.try_recv() {
+        match lookup {
+            Lookup::ScalarRuns {
+                logs_dir,
+                runs,
+                first,
+            } if *mp_struct.get_scalars_mode() => match runs {
+                Ok(runs) => {
+                    mp_struct.open_scalars(runs);
+                    match first {
+                        Some((run, scalars)) => show_scalars(mp_struct, &run, scalars),
+                        None => mp_struct.set_scalars_message(format!(
+                            "No TensorBoard event files under {}",
+                            logs_dir.display()
+                        )),
+                    }
+                }
+                Err(e) => mp_struct.set_scalars_message(format!(
+                    "Could not search {}: {}",
+                    logs_dir.display(),
+                    e
+                )),
+            },
+            // Only if that run is still the selected one
+            Lookup::Scalars(run, scalars)
+                if *mp_struct.get_scalars_mode()
+                    && mp_struct.get_selected_scalar_run() == Some(&run) =>
+            {
+                show_scalars(mp_struct, &run, scalars)
+            }
+            Lookup::CheckpointRuns { logs_dir, runs } if *mp_struct.get_checkpoints_mode() => {
+                match runs {
+                    Ok(runs) => {
+                        let found_any = !runs.is_empty();
+                        mp_struct.open_checkpoints(runs);
+                        if !found_any {
+                            mp_struct.set_checkpoints_message(format!(
+                                "No model_*.pt checkpoints under {}",
+                                logs_dir.display()
+                            ));
+                        }
+                    }
+                    Err(e) => mp_struct.set_checkpoints_message(format!(
+                        "Could not search {}: {}",
+                        logs_dir.display(),
+                        e
+                    )),
+                }
+            }
+            Lookup::ConfigDiff(lines) if mp_struct.get_diff_preview().is_some() => match lines {
+                Ok(lines) => mp_struct.update_diff_preview(lines),
+                // Back to the popup, to fix the directory
+                Err(e) => {
+                    mp_struct.close_diff_preview();
+                    mp_struct.set_create_error(e);
+                }
+            },
+            _ => {}
+        }
+    }
+}
+
+fn checkpoints_browsing(mp_struct: &mut Mainpage, app: &mut App) {
+    match handle_key_input(Duration::from_micros(5000), false) {
+        Some(Actions::Quit) | Some(Actions::Checkpoints) => mp_struct.close_checkpoints(),
+        Some(Actions::Moveup) => mp_struct.decrease_checkpoint_selection(),
+        Some(Actions::Movedown) => mp_struct.increase_checkpoint_selection(),
+        Some(Actions::Tab) => {
+            let files_focused = *mp_struct.get_checkpoint_files_focused();
+            mp_struct.set_checkpoint_files_focused(!files_focused);
+        }
+        Some(Actions::Enter) if !*mp_struct.get_checkpoint_files_focused() => {
+            mp_struct.set_checkpoint_files_focused(true);
+        }
+        // Queue a copy of the selected task that resumes from the checkpoint
+        Some(Actions::Enter) => {
+            let Some((run, checkpoint)) = mp_struct.get_selected_checkpoint() else {
+                return;
+            };
+            let resume = ResumeFrom::new(run.get_experiment(), run.get_run(), checkpoint);
+            let message = match app.queue_resume_task(mp_struct.get_selected_task_index(), resume) {
+                Some(name) => format!("Queued {}", name),
+                None => String::from("The task to resume is no longer in the queue"),
+            };
+            mp_struct.set_checkpoints_message(message);
+            mp_struct.update_task_list(app.get_task_queue_names());
+        }
+        // Queue an evaluation of the selected checkpoint with the task's play script
+        Some(Actions::Play(video)) => {
+            let Some((run, checkpoint)) = mp_struct.get_selected_checkpoint() else {
+                return;
+            };
+            // Relative to the task's directory, where play.py runs, so remote `~` paths work too
+            let checkpoint_path = Path::new(runner::LOGS_DIR).join(run.checkpoint_path(checkpoint));
+            let checkpoint_path = checkpoint_path.to_string_lossy().to_string();
+            let message = match app.queue_play_task(
+                mp_struct.get_selected_task_index(),
+                checkpoint_path,
+                video,
+            ) {
+                Some(name) if video => format!(
+                    "Queued {}, its video goes to {}/videos/play",
+                    name,
+                    run.label()
+                ),
+                Some(name) => format!("Queued {}", name),
+                None => String::from("The task to evaluate is no longer in the queue"),
+            };
+            mp_struct.set_checkpoints_message(message);
+            mp_struct.update_task_list(app.get_task_queue_names());
+        }
+        Some(_) => {}
+        None => {
+            eprintln!("Error reading key input.");
+        }
+    }
+}
+
+fn task_creating(mp_struct: &mut Mainpage, app: &mut App, lookups: &Sender<Lookup>) {
     match handle_key_input(Duration::from_micros(5000), true) {
         // First, handle the None case from the first match
         None => {
@@ -177,15 +561,28 @@ fn task_creating(mp_struct: &mut Mainpage, app: &mut App) {
         Some(action) => {
             // Handle the template task logic if we have a valid key input
             let template_task = app.read_template_task();
-            match *template_task {
-                Some(ref task) => {
-                    mp_struct.update_temp_task(
-                        task.get_task_name(),
-                        task.get_environment(),
-                        task.get_directory(),
-                    );
-                }
-                _ => (),
+            if let Some(ref task) = *template_task {
+                mp_struct.update_temp_task(
+                    task.get_task_name(),
+                    task.get_environment(),
+                    task.get_directory(),
+                );
+            }
+            mp_struct.set_temp_location(app.get_location_buffer());
+            mp_struct.set_temp_container(app.get_container_buffer());
+            mp_struct.set_temp_env_vars(app.get_env_buffer());
+            mp_struct.set_temp_python_env(app.get_python_env_buffer());
+            mp_struct.set_temp_config_mode(app.get_config_mode_buffer());
+            mp_struct.set_temp_wandb(app.get_wandb_buffer());
+            mp_struct.set_temp_retry(app.get_retry_buffer());
+            mp_struct.set_temp_timeout(app.get_timeout_buffer());
+            mp_struct.set_temp_depends(app.get_depends_buffer());
+            mp_struct.set_creation_state(*app.get_creation_state());
+
+            // The diff preview takes the keys until it is confirmed or dismissed
+            if mp_struct.get_diff_preview().is_some() {
+                diff_previewing(mp_struct, app, action);
+                return;
             }
 
             // Now, handle different actions from the second match based on the key input
@@ -237,13 +634,25 @@ fn task_creating(mp_struct: &mut Mainpage, app: &mut App) {
                         mp_struct.write_buff_to_yaml();
                         mp_struct.toggle_update_yaml_selection()
                     } else {
-                        match mp_struct.take_yaml() {
-                            Some(yaml) => app.set_yaml(yaml),
-                            None => {}
+                        // Keep the editor's copy until the preview is confirmed
+                        if let Some(yaml) = mp_struct.get_yaml() {
+                            app.set_yaml(yaml.clone())
+                        }
+                        match app.build_template_task() {
+                            Ok(task) => {
+                                mp_struct.set_create_error("");
+                                mp_struct.open_diff_preview(vec![String::from(
+                                    "Checking the directory and reading the config file...",
+                                )]);
+                                mp_struct.set_diff_pending(true);
+                                let lookups = lookups.clone();
+                                thread::spawn(move || {
+                                    let _ = lookups
+                                        .send(Lookup::ConfigDiff(checked_config_diff(&task)));
+                                });
+                            }
+                            Err(e) => mp_struct.set_create_error(e),
                         }
-                        app.pass_template_to_task_list();
-                        mp_struct.update_task_list(app.get_task_queue_names());
-                        mp_struct.set_create_window(false);
                     }
                 }
                 Actions::None => {
@@ -259,34 +668,264 @@ fn task_creating(mp_struct: &mut Mainpage, app: &mut App) {
     }
 }
 
-fn run_bash_command(command: &str) {
-    let status = Command::new("bash")
-        .arg("-c")
-        .arg(command)
-        .status()
-        .expect("Failed to execute command");
+// What a worker needs to run one task, set up on the UI thread
+struct TaskRun {
+    log: SharedLog,
+    control: SharedControl,
+    backups: Option<SharedBackups>,
+    run_dir: PathBuf,
+    started_at: u64,
+    log_path: PathBuf,
+}
+
+impl TaskRun {
+    // Prepares the config, runs the task and records it, all on the worker thread
+    fn run(self, task: &Task) -> TaskStatus {
+        let log = &self.log;
+        let executor = executor::for_task(task);
+        let yaml_path = executor.config_path(task);
 
-    if !status.success() {
-        eprintln!("Command failed: {}", command);
-        exit(1); // Or handle failure appropriately
+        let mut backups = self.backups.as_deref();
+        let backed_up = match backups {
+            Some(backups) => backups
+                .back_up(&*executor, &yaml_path)
+                .map_err(|e| format!("Could not back up {}: {}", yaml_path.display(), e).into()),
+            None => Ok(true),
+        };
+        match &backed_up {
+            Ok(true) => {}
+            Ok(false) => log.lock().unwrap().push_line(format!(
+                "No existing config at {}, it is removed again after the run",
+                yaml_path.display()
+            )),
+            // Writing over a config that can't be put back is worse than not running
+            Err(_) => backups = None,
+        }
+
+        let (command_line, outcome) =
+            match backed_up.and_then(|_| prepare_launch_command(&*executor, task, &yaml_path)) {
+                Ok(command) => {
+                    let command_line = format!(
+                        "cd {} && {}",
+                        runner::shell_quote(&command.get_working_dir().to_string_lossy()),
+                        command.to_shell_string()
+                    );
+                    if let Err(e) = history::write_run_snapshot(
+                        &self.run_dir,
+                        task.get_yaml(),
+                        &command_line,
+                        task.get_env_vars(),
+                    ) {
+                        log.lock()
+                            .unwrap()
+                            .push_line(format!("Could not write run snapshot: {}", e));
+                    }
+                    let outcome = run_launch_command(
+                        &*executor,
+                        &command,
+                        log,
+                        &self.control,
+                        task.get_timeout(),
+                    );
+                    (command.to_shell_string(), outcome)
+                }
+                Err(e) => {
+                    log.lock()
+                        .unwrap()
+                        .push_line(format!("Failed to prepare the task's config: {:?}", e));
+                    (String::new(), TaskStatus::Failed(-1))
+                }
+            };
+        restore_config(backups, &*executor, &yaml_path, log);
+
+        let mut record = RunRecord::new(
+            task,
+            command_line,
+            self.started_at,
+            self.log_path.display().to_string(),
+        );
+        record.finish(outcome, history::now_unix_secs());
+        record.set_wandb_url(log.lock().unwrap().get_wandb_url().map(String::from));
+        if let Err(e) = history::append_record(&history::history_path(), &record) {
+            log.lock()
+                .unwrap()
+                .push_line(format!("Could not write run history: {}", e));
+        }
+        log.lock().unwrap().close_file();
+        outcome
+    }
+}
+
+// Runs a task on the worker thread and works out how it ended
+fn run_launch_command(
+    executor: &dyn Executor,
+    command: &LaunchCommand,
+    log: &SharedLog,
+    control: &SharedControl,
+    timeout: Option<Duration>,
+) -> TaskStatus {
+    let outcome = match executor.run(command, log, control, timeout) {
+        Ok(_) if control.is_timed_out() => TaskStatus::TimedOut,
+        Ok(_) if control.is_cancelled() => TaskStatus::Cancelled,
+        Ok(status) if status.success() => TaskStatus::Succeeded,
+        // Shell convention: killed by signal N reports as 128 + N
+        Ok(status) => TaskStatus::Failed(
+            status
+                .code()
+                .or(status.signal().map(|signal| 128 + signal))
+                .unwrap_or(-1),
+        ),
+        Err(e) => {
+            log.lock()
+                .unwrap()
+                .push_line(format!("Failed to start command: {}", e));
+            TaskStatus::Failed(-1)
+        }
+    };
+    log.lock()
+        .unwrap()
+        .push_line(format!("Task finished: {}", outcome.label()));
+    outcome
+}
+
+// Keys while the config diff is shown: Enter queues the task, Esc goes back to the popup
+fn diff_previewing(mp_struct: &mut Mainpage, app: &mut App, action: Actions) {
+    match action {
+        // Not before the directory check is back
+        Actions::Enter if mp_struct.get_diff_pending() => {}
+        Actions::Enter => {
+            mp_struct.close_diff_preview();
+            let _ = mp_struct.take_yaml();
+            match app.pass_template_to_task_list() {
+                Ok(_) => {
+                    mp_struct.set_create_error("");
+                    mp_struct.update_task_list(app.get_task_queue_names());
+                    mp_struct.set_create_window(false);
+                }
+                Err(e) => mp_struct.set_create_error(e),
+            }
+        }
+        Actions::Quit => mp_struct.close_diff_preview(),
+        Actions::Moveup => mp_struct.scroll_diff_up(1),
+        Actions::Movedown => mp_struct.scroll_diff_down(1),
+        Actions::ScrollUp => mp_struct.scroll_diff_up(LOG_SCROLL_STEP),
+        Actions::ScrollDown => mp_struct.scroll_diff_down(LOG_SCROLL_STEP),
+        _ => {}
+    }
+}
+
+// The config diff, once a remote task's directory is known to hold Isaac Lab
+fn checked_config_diff(task: &Task) -> LookupResult<Vec<String>> {
+    if let TaskLocation::Ssh(_) = task.get_location() {
+        directory::validate_isaaclab_dir_on(&*executor::for_task(task), task.get_directory())?;
     }
+    Ok(config_diff(task))
 }
 
-// Write a generic type T to a YAML file
-fn write_yaml<T>(file_path: &str, data: &T) -> Result<(), Box<dyn std::error::Error>>
+/// Unified diff of the task's config file as it is on disk against the
+/// YAML edited in the popup, for the user to check before queueing.
+fn config_diff(task: &Task) -> Vec<String> {
+    let executor = executor::for_task(task);
+    let yaml_path = executor.config_path(task);
+    let path_label = yaml_path.display().to_string();
+    let Some(edited) = task.get_yaml() else {
+        return vec![format!("No YAML edits, {} is used as it is.", path_label)];
+    };
+    let edited = match serde_yaml::to_string(edited) {
+        Ok(edited) => edited,
+        Err(e) => return vec![format!("Could not serialize the edited YAML: {}", e)],
+    };
+    let (on_disk, disk_label) = match executor.read_config(&yaml_path) {
+        Ok(contents) => (contents, path_label.clone()),
+        Err(_) => (String::new(), String::from("/dev/null")), // Would be a new file
+    };
+
+    let mut lines = vec![];
+    if task.get_config_mode() == ConfigMode::Overrides {
+        lines.push(String::from(
+            "Passed as command-line overrides, the file itself is left unchanged.",
+        ));
+    }
+    let diff = uis::diff::unified_diff(&on_disk, &edited, &disk_label, &path_label);
+    if diff.is_empty() {
+        lines.push(format!("No changes to {}", path_label));
+    }
+    lines.extend(diff);
+    lines
+}
+
+/// Gets the task's edited YAML to the run: written over its config file, or
+/// as Hydra overrides against that file in `ConfigMode::Overrides`.
+fn prepare_launch_command(
+    executor: &dyn Executor,
+    task: &Task,
+    yaml_path: &Path,
+) -> Result<LaunchCommand, Box<dyn std::error::Error>> {
+    match task.get_config_mode() {
+        ConfigMode::File => {
+            // Without edits the file is left as it is rather than overwritten with `null`
+            if let Some(yaml) = task.get_yaml() {
+                write_yaml(executor, yaml_path, yaml)?;
+            }
+            // Only hand the file to Hydra when there was a config to write
+            let written_yaml = task.get_yaml().as_ref().map(|_| yaml_path);
+            Ok(runner::build_launch_command(task, written_yaml))
+        }
+        ConfigMode::Overrides => {
+            // Hydra still loads the untouched file on disk, the edits go on top of it
+            let base = match (executor.read_config(yaml_path), task.get_yaml()) {
+                (Ok(base), _) => Some(base),
+                (Err(_), None) => None, // No file and nothing to override, Hydra's defaults it is
+                (Err(e), Some(_)) => return Err(e),
+            };
+            let mut command = runner::build_launch_command(task, base.as_ref().map(|_| yaml_path));
+            if let (Some(base), Some(edited)) = (base, task.get_yaml()) {
+                let base: Value = serde_yaml::from_str(&base)?;
+                for arg in uis::yaml_overrides(&base, edited) {
+                    command.arg(arg);
+                }
+            }
+            Ok(command)
+        }
+    }
+}
+
+// Puts the config file back as it was before the task, if this run backed it up
+fn restore_config(
+    backups: Option<&ConfigBackups>,
+    executor: &dyn Executor,
+    yaml_path: &Path,
+    log: &SharedLog,
+) {
+    let Some(backups) = backups else {
+        return;
+    };
+    match backups.restore(executor, yaml_path) {
+        Ok(true) => log.lock().unwrap().push_line(format!(
+            "Put the config at {} back as it was",
+            yaml_path.display()
+        )),
+        Ok(false) => {}
+        Err(e) => log.lock().unwrap().push_line(format!(
+            "Could not restore config at {}: {}",
+            yaml_path.display(),
+            e
+        )),
+    }
+}
+
+// Write a generic type T to a YAML file, wherever the task's executor keeps it
+fn write_yaml<T>(
+    executor: &dyn Executor,
+    file_path: &Path,
+    data: &T,
+) -> Result<(), Box<dyn std::error::Error>>
 where
     T: Serialize, // The type T must be serializable
 {
     // Serialize the data into a YAML string
     let yaml_string = serde_yaml::to_string(data)?;
 
-    // Open the file in write mode. If the file doesn't exist, it will be created.
-    // If it exists, its content will be truncated (overwritten).
-    let path = Path::new(file_path);
-    let mut file = File::create(path)?;
-
     // Write the YAML string to the file
-    file.write_all(yaml_string.as_bytes())?;
-
-    Ok(())
+    executor.write_config(file_path, &yaml_string)
 }
@@ -0,0 +1,192 @@
+use super::executor::Executor;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// What is kept on disk for one config file, enough to put it back by hand
+// if the app dies before restoring it
+#[derive(Serialize, Deserialize)]
+struct SavedOriginal {
+    executor: String,
+    path: PathBuf,
+    original: Option<String>, // None when there was no file, so restoring deletes it
+}
+
+// Where one config file's original is saved and how many runs still have it replaced
+struct Backup {
+    saved_at: PathBuf,
+    users: usize,
+}
+
+/// Originals of the config files tasks write over, saved under `dir` and
+/// restored once the last task using a file is done. Runs sharing a file
+/// share one backup, so the file ends up as it was before the first of them,
+/// not as one of them wrote it.
+pub struct ConfigBackups {
+    dir: PathBuf,
+    backups: Mutex<HashMap<(String, PathBuf), Backup>>, // Keyed by executor label and path
+}
+
+pub type SharedBackups = Arc<ConfigBackups>;
+
+impl ConfigBackups {
+    pub fn new_shared(dir: PathBuf) -> SharedBackups {
+        Arc::new(Self {
+            dir,
+            backups: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Saves the file's current contents unless a running task already has.
+    /// An original left behind by an earlier session that never restored it
+    /// is kept instead, as the file now holds what that session wrote.
+    /// Returns false if there was no file, which `restore` then removes again.
+    /// Fails when the file couldn't be read or saved, e.g. with the SSH host down.
+    pub fn back_up(
+        &self,
+        executor: &dyn Executor,
+        path: &Path,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut backups = self.backups.lock().unwrap();
+        let key = (executor.label(), path.to_path_buf());
+        let saved_at = self.dir.join(backup_file_name(&key));
+        let had_original = match backups.entry(key) {
+            Entry::Occupied(mut entry) => {
+                entry.get_mut().users += 1;
+                load_original(&entry.get().saved_at)?.original.is_some()
+            }
+            Entry::Vacant(entry) => {
+                let saved = match load_original(&saved_at) {
+                    Ok(saved) => saved,
+                    Err(_) => {
+                        let original = match executor.config_exists(path)? {
+                            true => Some(executor.read_config(path)?),
+                            false => None,
+                        };
+                        let saved = SavedOriginal {
+                            executor: executor.label(),
+                            path: path.to_path_buf(),
+                            original,
+                        };
+                        save_original(&saved_at, &saved)?;
+                        saved
+                    }
+                };
+                entry.insert(Backup { saved_at, users: 1 });
+                saved.original.is_some()
+            }
+        };
+        Ok(had_original)
+    }
+
+    /// Puts the original back, or removes a file that wasn't there before,
+    /// once no other running task uses it. The saved copy is deleted only
+    /// after that worked.
+    pub fn restore(
+        &self,
+        executor: &dyn Executor,
+        path: &Path,
+    ) -> Result<bool, Box<dyn std::error::Error>> {
+        let mut backups = self.backups.lock().unwrap();
+        let key = (executor.label(), path.to_path_buf());
+        let Some(backup) = backups.get_mut(&key) else {
+            return Ok(false);
+        };
+        backup.users -= 1;
+        if backup.users > 0 {
+            return Ok(false); // Still in use, the last one out restores it
+        }
+        let Some(backup) = backups.remove(&key) else {
+            return Ok(false);
+        };
+        match load_original(&backup.saved_at)?.original {
+            Some(contents) => executor.write_config(path, &contents)?,
+            None => executor.remove_config(path)?,
+        }
+        fs::remove_file(&backup.saved_at)?;
+        Ok(true)
+    }
+}
+
+// Stable for a given config file, so a later session finds what an earlier one left
+fn backup_file_name(key: &(String, PathBuf)) -> String {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    format!("{:016x}.yaml", hasher.finish())
+}
+
+fn load_original(saved_at: &Path) -> Result<SavedOriginal, Box<dyn std::error::Error>> {
+    Ok(serde_yaml::from_str(&fs::read_to_string(saved_at)?)?)
+}
+
+// Synced before the config is written over, so a crash can't lose both copies
+fn save_original(saved_at: &Path, saved: &SavedOriginal) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = saved_at.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = saved_at.with_extension("yaml.tmp");
+    let mut file = File::create(&tmp_path)?;
+    file.write_all(serde_yaml::to_string(saved)?.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, saved_at)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::executor::LocalExecutor;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pushing_launch backup {} {}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn original_is_restored_from_disk_and_cleaned_up() {
+        let dir = scratch_dir("restore");
+        let config = dir.join("agent.yaml");
+        fs::write(&config, "lr: 0.1\n").unwrap();
+        let backups = ConfigBackups::new_shared(dir.join("backups"));
+
+        assert!(backups.back_up(&LocalExecutor, &config).unwrap());
+        assert!(backups.back_up(&LocalExecutor, &config).unwrap());
+        fs::write(&config, "lr: 0.5\n").unwrap();
+        assert_eq!(fs::read_dir(dir.join("backups")).unwrap().count(), 1);
+
+        assert!(!backups.restore(&LocalExecutor, &config).unwrap());
+        assert_eq!(fs::read_to_string(&config).unwrap(), "lr: 0.5\n");
+        assert!(backups.restore(&LocalExecutor, &config).unwrap());
+        assert_eq!(fs::read_to_string(&config).unwrap(), "lr: 0.1\n");
+        assert_eq!(fs::read_dir(dir.join("backups")).unwrap().count(), 0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn original_left_by_an_earlier_session_wins() {
+        let dir = scratch_dir("leftover");
+        let config = dir.join("agent.yaml");
+        fs::write(&config, "lr: 0.1\n").unwrap();
+        let crashed = ConfigBackups::new_shared(dir.join("backups"));
+        crashed.back_up(&LocalExecutor, &config).unwrap();
+        fs::write(&config, "lr: 0.5\n").unwrap();
+
+        let backups = ConfigBackups::new_shared(dir.join("backups"));
+        backups.back_up(&LocalExecutor, &config).unwrap();
+        fs::write(&config, "lr: 0.9\n").unwrap();
+        backups.restore(&LocalExecutor, &config).unwrap();
+        assert_eq!(fs::read_to_string(&config).unwrap(), "lr: 0.1\n");
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,96 @@
+use super::executor::Executor;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+// RSL-RL saves `model_<iteration>.pt` every save interval and at the end
+pub const CHECKPOINT_PREFIX: &str = "model_";
+pub const CHECKPOINT_SUFFIX: &str = ".pt";
+
+/// One training run's folder and the checkpoints saved in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointRun {
+    framework: String,
+    experiment: String,
+    run: String,
+    dir: PathBuf,
+    checkpoints: Vec<String>, // File names, latest iteration first
+}
+
+impl CheckpointRun {
+    pub fn get_experiment(&self) -> &str {
+        &self.experiment
+    }
+    pub fn get_run(&self) -> &str {
+        &self.run
+    }
+    pub fn get_dir(&self) -> &Path {
+        &self.dir
+    }
+    pub fn get_checkpoints(&self) -> &[String] {
+        &self.checkpoints
+    }
+    /// Where one of the run's checkpoints is, relative to the logs directory.
+    pub fn checkpoint_path(&self, checkpoint: &str) -> PathBuf {
+        Path::new(&self.framework)
+            .join(&self.experiment)
+            .join(&self.run)
+            .join(checkpoint)
+    }
+    pub fn label(&self) -> String {
+        format!("{}/{}/{}", self.framework, self.experiment, self.run)
+    }
+}
+
+/// Runs with checkpoints at `logs_dir/<framework>/<experiment>/<run>/model_*.pt`,
+/// newest run first (Isaac Lab names run folders by their start time).
+pub fn find_checkpoint_runs(
+    executor: &dyn Executor,
+    logs_dir: &Path,
+) -> Result<Vec<CheckpointRun>, Box<dyn std::error::Error>> {
+    let mut runs: BTreeMap<(String, String, String), Vec<String>> = BTreeMap::new();
+    for file in executor.find_files(logs_dir, CHECKPOINT_PREFIX)? {
+        let Ok(relative) = file.strip_prefix(logs_dir) else {
+            continue;
+        };
+        let parts: Vec<String> = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect();
+        // Anything deeper or shallower isn't a run's checkpoint, e.g. exported policies
+        let Ok([framework, experiment, run, name]) = <[String; 4]>::try_from(parts) else {
+            continue;
+        };
+        if name.ends_with(CHECKPOINT_SUFFIX) {
+            runs.entry((framework, experiment, run))
+                .or_default()
+                .push(name);
+        }
+    }
+
+    let mut runs: Vec<CheckpointRun> = runs
+        .into_iter()
+        .map(|((framework, experiment, run), mut checkpoints)| {
+            checkpoints.sort_by_key(|name| std::cmp::Reverse(checkpoint_iteration(name)));
+            CheckpointRun {
+                dir: logs_dir.join(&framework).join(&experiment).join(&run),
+                framework,
+                experiment,
+                run,
+                checkpoints,
+            }
+        })
+        .collect();
+    runs.sort_by(|a, b| b.run.cmp(&a.run));
+    Ok(runs)
+}
+
+/// The iteration in `model_<iteration>.pt`, if the name has one.
+pub fn checkpoint_iteration(name: &str) -> Option<u64> {
+    name.strip_prefix(CHECKPOINT_PREFIX)?
+        .strip_suffix(CHECKPOINT_SUFFIX)?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,129 @@
+use std::io;
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// How long a task gets to shut down after SIGTERM before it is SIGKILLed
+pub const CANCEL_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Shared between the TUI and the worker thread so a running task can be stopped.
+#[derive(Debug, Default)]
+pub struct RunControl {
+    pid: Mutex<Option<u32>>, // Process group of the task, until the whole group is gone
+    cancel_requested: AtomicBool,
+    timed_out: AtomicBool, // Stopped because it ran past its timeout, not by the user
+    remote_state: Mutex<Option<String>>, // Scheduler-side state, e.g. a Slurm job's PENDING
+}
+
+pub type SharedControl = Arc<RunControl>;
+
+impl RunControl {
+    pub fn new_shared() -> SharedControl {
+        Arc::new(Self::default())
+    }
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel_requested.load(Ordering::SeqCst)
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::SeqCst)
+    }
+
+    pub fn get_remote_state(&self) -> Option<String> {
+        self.remote_state.lock().unwrap().clone()
+    }
+    pub fn set_remote_state(&self, state: Option<String>) {
+        *self.remote_state.lock().unwrap() = state;
+    }
+
+    // Called by the worker once the child is spawned
+    pub fn set_pid(&self, pid: Option<u32>) {
+        *self.pid.lock().unwrap() = pid;
+        // A cancel that arrived before the spawn still has to land
+        if pid.is_some() && self.is_cancelled() {
+            self.signal("TERM");
+        }
+    }
+
+    /// `child.try_wait()` under the pid lock, so a signal never goes out while
+    /// the leader is being reaped. A task that exited by itself lets go of its
+    /// group right away; a cancelled one keeps it until `release_group`.
+    pub fn reap(&self, child: &mut Child) -> io::Result<Option<ExitStatus>> {
+        let mut pid = self.pid.lock().unwrap();
+        let status = child.try_wait();
+        if matches!(status, Ok(Some(_))) && !self.is_cancelled() {
+            *pid = None;
+        }
+        status
+    }
+
+    /// Waits until nothing is left of the task's process group, then forgets
+    /// it. After a cancel, children that outlived the leader still get the
+    /// SIGKILL escalation, and the group id can't be reused while they live.
+    pub fn release_group(&self, poll_interval: Duration) {
+        loop {
+            let mut pid = self.pid.lock().unwrap();
+            match *pid {
+                Some(group) if self.is_cancelled() && group_alive(group) => {}
+                _ => {
+                    *pid = None;
+                    return;
+                }
+            }
+            drop(pid);
+            thread::sleep(poll_interval);
+        }
+    }
+
+    /// Sends SIGTERM to the task and SIGKILL if it is still alive after `grace`.
+    pub fn cancel(self: &Arc<Self>, grace: Duration) {
+        if self.cancel_requested.swap(true, Ordering::SeqCst) {
+            return; // Already on its way down
+        }
+        self.signal("TERM");
+
+        let control = Arc::clone(self);
+        thread::spawn(move || {
+            thread::sleep(grace);
+            control.signal("KILL");
+        });
+    }
+
+    /// Stops the task the same way as `cancel`, but records it as a timeout.
+    pub fn time_out(self: &Arc<Self>, grace: Duration) {
+        if !self.is_cancelled() {
+            self.timed_out.store(true, Ordering::SeqCst);
+        }
+        self.cancel(grace);
+    }
+
+    fn signal(&self, signal: &str) {
+        // `reap` and `release_group` take this lock too, so the group can't be freed mid-signal
+        let pid = self.pid.lock().unwrap();
+        if let Some(pid) = *pid {
+            // Negative pid targets the whole group, so python under isaaclab.sh goes too
+            let _ = Command::new("kill")
+                .arg("-s")
+                .arg(signal)
+                .arg("--")
+                .arg(format!("-{}", pid))
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status();
+        }
+    }
+}
+
+// Whether any process is still in the group; `kill -0` checks without signalling
+fn group_alive(group: u32) -> bool {
+    Command::new("kill")
+        .arg("-0")
+        .arg("--")
+        .arg(format!("-{}", group))
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
@@ -0,0 +1,183 @@
+use super::Executor;
+use crate::app::history::now_unix_secs;
+use crate::app::task_config::{
+    Task,
+    container::{ContainerRuntime, ContainerSpec},
+};
+use crate::runner::{
+    LaunchCommand,
+    control::{CANCEL_GRACE_PERIOD, SharedControl},
+    log_buffer::SharedLog,
+};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitStatus};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+// Keeps container names unique when several tasks start in the same second
+static CONTAINER_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Runs a task's command inside a container, on whatever machine `inner`
+/// runs things. The host paths the task needs are mounted at the same paths
+/// inside, so the command and its `--config-path` work unchanged. Paths are
+/// made absolute by `inner`, so they are right on a remote host too.
+pub struct ContainerExecutor {
+    inner: Box<dyn Executor>,
+    spec: ContainerSpec,
+    mounts: Vec<PathBuf>,
+}
+
+impl ContainerExecutor {
+    pub fn new(inner: Box<dyn Executor>, spec: ContainerSpec, task: &Task) -> Self {
+        let mut mounts = vec![inner.resolve_dir(Path::new(task.get_directory()))];
+        if let Some(config_dir) = inner.config_path(task).parent() {
+            let config_dir = inner.resolve_dir(config_dir);
+            if !mounts.contains(&config_dir) {
+                mounts.push(config_dir);
+            }
+        }
+        Self {
+            inner,
+            spec,
+            mounts,
+        }
+    }
+
+    /// `command` wrapped in `docker run` or `apptainer exec`.
+    pub fn wrap(&self, command: &LaunchCommand) -> LaunchCommand {
+        let working_dir = self.inner.resolve_dir(command.get_working_dir());
+        let workdir_arg = working_dir.to_string_lossy().to_string();
+        let mut wrapped = LaunchCommand::new(self.spec.get_runtime().label(), working_dir.clone());
+        let container_name = format!(
+            "pushing_launch_{}_{}_{}",
+            now_unix_secs(),
+            process::id(),
+            CONTAINER_COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        match self.spec.get_runtime() {
+            ContainerRuntime::Docker => {
+                // --init so the task's python gets signals and its children are reaped
+                wrapped
+                    .arg("run")
+                    .arg("--rm")
+                    .arg("--init")
+                    .arg("--name")
+                    .arg(container_name.as_str());
+                for mount in &self.mounts {
+                    let mount = mount.to_string_lossy();
+                    wrapped.arg("-v").arg(format!("{}:{}", mount, mount));
+                }
+                wrapped.arg("-w").arg(workdir_arg);
+            }
+            ContainerRuntime::Apptainer => {
+                wrapped.arg("exec");
+                for mount in &self.mounts {
+                    let mount = mount.to_string_lossy();
+                    wrapped.arg("--bind").arg(format!("{}:{}", mount, mount));
+                }
+                wrapped.arg("--pwd").arg(workdir_arg);
+            }
+        }
+        // Variables set on the runtime's CLI wouldn't reach the container
+        for (key, value) in command.get_env() {
+            let env_flag = match self.spec.get_runtime() {
+                ContainerRuntime::Docker => "-e",
+                ContainerRuntime::Apptainer => "--env",
+            };
+            wrapped.arg(env_flag).arg(format!("{}={}", key, value));
+        }
+        for flag in self.spec.get_flags() {
+            wrapped.arg(flag.as_str());
+        }
+        wrapped.arg(self.spec.get_image());
+        if command.get_setup().is_empty() {
+            wrapped.arg(command.get_program());
+            for arg in command.get_args() {
+                wrapped.arg(arg.as_str());
+            }
+        } else {
+            // Setup steps (e.g. conda activate) have to run inside the container too
+            wrapped.arg("bash").arg("-c").arg(command.to_shell_string());
+        }
+        match self.spec.get_runtime() {
+            ContainerRuntime::Docker => stop_container_on_signal(&wrapped, &container_name),
+            ContainerRuntime::Apptainer => wrapped, // Runs as a plain child, signals reach it
+        }
+    }
+}
+
+// The container belongs to the docker daemon, so killing the `docker run`
+// client (or losing the ssh connection to it) would leave it running. A
+// shell around it stops the container by name when the task is cancelled.
+fn stop_container_on_signal(docker_run: &LaunchCommand, container_name: &str) -> LaunchCommand {
+    // Has to be done before the SIGKILL that follows the grace period. A
+    // trapped signal ends `wait` early, so it waits again for the exit code.
+    let stop_after = (CANCEL_GRACE_PERIOD / 2).as_secs();
+    let script = format!(
+        "{docker_run} &\n\
+         pid=$!\n\
+         trap 'docker stop --time {stop_after} {name} >/dev/null 2>&1 || docker kill {name} >/dev/null 2>&1' TERM INT HUP\n\
+         wait $pid; code=$?\n\
+         while kill -0 $pid 2>/dev/null; do wait $pid; code=$?; done\n\
+         exit $code",
+        docker_run = docker_run.to_shell_string(),
+        name = container_name,
+    );
+    let mut wrapped = LaunchCommand::new("bash", docker_run.get_working_dir().to_path_buf());
+    wrapped.arg("-c").arg(script);
+    wrapped
+}
+
+impl Executor for ContainerExecutor {
+    fn label(&self) -> String {
+        format!("{} ({})", self.inner.label(), self.spec.label())
+    }
+
+    fn config_path(&self, task: &Task) -> PathBuf {
+        self.inner.config_path(task)
+    }
+
+    fn resolve_dir(&self, dir: &Path) -> PathBuf {
+        self.inner.resolve_dir(dir)
+    }
+
+    fn read_config(&self, path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+        self.inner.read_config(path)
+    }
+
+    fn write_config(&self, path: &Path, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.write_config(path, contents)
+    }
+
+    fn config_exists(&self, path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+        self.inner.config_exists(path)
+    }
+
+    fn remove_config(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        self.inner.remove_config(path)
+    }
+
+    // The task's directory is mounted at the same path, so its logs are on the host
+    fn find_files(
+        &self,
+        dir: &Path,
+        prefix: &str,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        self.inner.find_files(dir, prefix)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        self.inner.read_file(path)
+    }
+
+    fn run(
+        &self,
+        command: &LaunchCommand,
+        log: &SharedLog,
+        control: &SharedControl,
+        timeout: Option<Duration>,
+    ) -> io::Result<ExitStatus> {
+        self.inner.run(&self.wrap(command), log, control, timeout)
+    }
+}
@@ -0,0 +1,159 @@
+pub mod container;
+pub mod slurm;
+pub mod ssh;
+use super::{LaunchCommand, control::SharedControl, log_buffer::SharedLog};
+use crate::app::task_config::{Task, TaskLocation};
+use container::ContainerExecutor;
+use slurm::SlurmExecutor;
+use ssh::{SshExecutor, SshTransport};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{self, Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+
+/// A place tasks can run. Writing the config and running the command both
+/// go through the executor, so a task's files end up where it runs.
+pub trait Executor: Send {
+    // Shown as "Task Location" in the Info panel
+    fn label(&self) -> String;
+
+    /// Path of the task's YAML config as seen by the machine running it.
+    fn config_path(&self, task: &Task) -> PathBuf;
+
+    /// `dir` made absolute on the machine running tasks, or as given if that fails.
+    fn resolve_dir(&self, dir: &Path) -> PathBuf;
+
+    fn read_config(&self, path: &Path) -> Result<String, Box<dyn std::error::Error>>;
+
+    fn write_config(&self, path: &Path, contents: &str) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Whether there is a file at `path`; an error means it couldn't be checked.
+    fn config_exists(&self, path: &Path) -> Result<bool, Box<dyn std::error::Error>>;
+
+    /// Deletes the file at `path`, if there is one.
+    fn remove_config(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// Files anywhere under `dir` whose name starts with `prefix`, e.g. the
+    /// TensorBoard event files a run left in its log directory.
+    fn find_files(
+        &self,
+        dir: &Path,
+        prefix: &str,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>>;
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>>;
+
+    /// Runs the command to completion, with output going to `log`.
+    fn run(
+        &self,
+        command: &LaunchCommand,
+        log: &SharedLog,
+        control: &SharedControl,
+        timeout: Option<Duration>,
+    ) -> io::Result<ExitStatus>;
+}
+
+pub fn for_location(location: &TaskLocation) -> Box<dyn Executor> {
+    match location {
+        TaskLocation::Local => Box::new(LocalExecutor),
+        TaskLocation::Ssh(host) => Box::new(SshExecutor::new(SshTransport::new(host))),
+        TaskLocation::Slurm(options) => Box::new(SlurmExecutor::new(options)),
+    }
+}
+
+/// The executor for a task: its location, inside its container if it has one.
+pub fn for_task(task: &Task) -> Box<dyn Executor> {
+    let executor = for_location(task.get_location());
+    match task.get_container() {
+        Some(spec) => Box::new(ContainerExecutor::new(executor, spec.clone(), task)),
+        None => executor,
+    }
+}
+
+/// Runs tasks on this machine, from the task's directory.
+pub struct LocalExecutor;
+
+impl Executor for LocalExecutor {
+    fn label(&self) -> String {
+        TaskLocation::Local.label()
+    }
+
+    fn config_path(&self, task: &Task) -> PathBuf {
+        // Hydra resolves relative config paths against the script, not the shell
+        self.resolve_dir(Path::new(task.get_directory()))
+            .join(task.get_config_file())
+    }
+
+    fn resolve_dir(&self, dir: &Path) -> PathBuf {
+        path::absolute(dir).unwrap_or_else(|_| dir.to_path_buf())
+    }
+
+    fn read_config(&self, path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(fs::read_to_string(path)?)
+    }
+
+    fn write_config(&self, path: &Path, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+        // Write next to the target and rename over it, so a crash never leaves half a config
+        let tmp_path = tmp_path_for(path);
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(contents.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    fn config_exists(&self, path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+        Ok(path.try_exists()?)
+    }
+
+    fn remove_config(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn find_files(
+        &self,
+        dir: &Path,
+        prefix: &str,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let mut found = vec![];
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(&dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                if entry.file_type()?.is_dir() {
+                    pending.push(path);
+                } else if entry.file_name().to_string_lossy().starts_with(prefix) {
+                    found.push(path);
+                }
+            }
+        }
+        found.sort();
+        Ok(found)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        Ok(fs::read(path)?)
+    }
+
+    fn run(
+        &self,
+        command: &LaunchCommand,
+        log: &SharedLog,
+        control: &SharedControl,
+        timeout: Option<Duration>,
+    ) -> io::Result<ExitStatus> {
+        super::run_with_output(command, log, control, timeout)
+    }
+}
+
+// `<file>.tmp` in the same directory, so the rename stays on one filesystem
+pub fn tmp_path_for(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
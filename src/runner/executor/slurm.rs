@@ -0,0 +1,627 @@
+use super::{Executor, LocalExecutor};
+use crate::app::history::now_unix_secs;
+use crate::app::task_config::{Task, TaskLocation};
+use crate::runner::{
+    LaunchCommand, WAIT_POLL_INTERVAL,
+    control::{CANCEL_GRACE_PERIOD, SharedControl},
+    log_buffer::SharedLog,
+    shell_quote,
+};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::os::unix::process::ExitStatusExt;
+use std::path::{self, Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How often squeue is asked about a submitted job, kept low to spare the controller
+const SLURM_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// Batch scripts, job output and exit codes go here, under the task's directory
+const SLURM_DIR: &str = "logs/slurm";
+
+// Keeps script names unique when several tasks are submitted in the same second
+static SUBMISSION_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+// sacct states of a job that isn't over yet, even once squeue has let go of it
+const ACTIVE_STATES: [&str; 7] = [
+    "PENDING",
+    "CONFIGURING",
+    "RUNNING",
+    "COMPLETING",
+    "SUSPENDED",
+    "REQUEUED",
+    "RESIZING",
+];
+
+/// The Slurm client programs, by name or by path.
+#[derive(Debug, Clone)]
+struct SlurmPrograms {
+    sbatch: String,
+    squeue: String,
+    sacct: String,
+    scancel: String,
+}
+
+impl Default for SlurmPrograms {
+    fn default() -> Self {
+        Self {
+            sbatch: String::from("sbatch"),
+            squeue: String::from("squeue"),
+            sacct: String::from("sacct"),
+            scancel: String::from("scancel"),
+        }
+    }
+}
+
+impl SlurmPrograms {
+    // Stand-ins with the same names, all in `dir`
+    #[cfg(test)]
+    fn in_dir(dir: &Path) -> Self {
+        let program = |name: &str| dir.join(name).to_string_lossy().to_string();
+        Self {
+            sbatch: program("sbatch"),
+            squeue: program("squeue"),
+            sacct: program("sacct"),
+            scancel: program("scancel"),
+        }
+    }
+}
+
+// What squeue had to say about a job on one poll
+enum QueueState {
+    Listed(String),  // Still in the queue, e.g. PENDING
+    Gone,            // squeue no longer knows the job id
+    Unknown(String), // squeue failed for some other reason, tried again next poll
+}
+
+/// Submits tasks to Slurm with `sbatch` and follows them with `squeue`/`sacct`.
+/// The task's directory must be on a filesystem the compute nodes share.
+pub struct SlurmExecutor {
+    options: Vec<String>, // Passed to sbatch as they are, e.g. `-p gpu --time=2:00:00`
+    programs: SlurmPrograms,
+    poll_interval: Duration,
+}
+
+impl SlurmExecutor {
+    pub fn new(options: &str) -> Self {
+        Self {
+            options: options.split_whitespace().map(String::from).collect(),
+            programs: SlurmPrograms::default(),
+            poll_interval: SLURM_POLL_INTERVAL,
+        }
+    }
+
+    #[cfg(test)]
+    fn with_programs(options: &str, programs: SlurmPrograms, poll_interval: Duration) -> Self {
+        Self {
+            programs,
+            poll_interval,
+            ..Self::new(options)
+        }
+    }
+
+    /// The batch script for `command`. It writes the exit code to
+    /// `exit_file` so the result is known even where sacct isn't available.
+    pub fn batch_script(
+        &self,
+        command: &LaunchCommand,
+        output_file: &Path,
+        exit_file: &Path,
+    ) -> io::Result<String> {
+        let working_dir = command.get_working_dir().to_string_lossy();
+        let output_path = output_file.to_string_lossy();
+        // `#SBATCH` lines aren't read by a shell, so these can't be quoted reliably
+        for (option, path) in [("--chdir", &working_dir), ("--output", &output_path)] {
+            if path.contains(|c: char| c.is_whitespace() || c == '"' || c == '\'') {
+                return Err(io::Error::other(format!(
+                    "Slurm can't take a path with spaces or quotes for {}: {}",
+                    option, path
+                )));
+            }
+        }
+        let mut script = String::from("#!/bin/bash\n");
+        script.push_str("#SBATCH --job-name=pushing_launch\n");
+        script.push_str(&format!("#SBATCH --chdir={}\n", working_dir));
+        script.push_str(&format!("#SBATCH --output={}\n", output_path));
+        script.push_str(&format!("cd {} || exit 1\n", shell_quote(&working_dir)));
+        script.push_str(&format!("{}\n", command.to_shell_string()));
+        script.push_str("code=$?\n");
+        script.push_str(&format!(
+            "echo $code > {}\n",
+            shell_quote(&exit_file.to_string_lossy())
+        ));
+        script.push_str("exit $code\n");
+        Ok(script)
+    }
+
+    fn submit(&self, script_path: &Path, log: &SharedLog) -> io::Result<String> {
+        log.lock().unwrap().push_line(format!(
+            "$ sbatch --parsable {}{}",
+            self.options
+                .iter()
+                .map(|option| format!("{} ", shell_quote(option)))
+                .collect::<String>(),
+            script_path.display()
+        ));
+        // On the command line, so an option and its value may be separate words
+        let output = Command::new(&self.programs.sbatch)
+            .arg("--parsable")
+            .args(&self.options)
+            .arg(script_path)
+            .stdin(Stdio::null())
+            .output()?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "sbatch failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        // `--parsable` prints `<job id>` or `<job id>;<cluster>`
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let job_id = stdout.trim().split(';').next().unwrap_or_default();
+        if job_id.is_empty() {
+            return Err(io::Error::other("sbatch did not print a job id"));
+        }
+        Ok(job_id.to_string())
+    }
+
+    fn scancel(&self, job_id: &str, log: &SharedLog) {
+        log.lock()
+            .unwrap()
+            .push_line(format!("$ scancel {}", job_id));
+        let _ = Command::new(&self.programs.scancel)
+            .arg(job_id)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+
+    fn queue_state(&self, job_id: &str) -> io::Result<QueueState> {
+        let output = Command::new(&self.programs.squeue)
+            .arg("-h")
+            .arg("-j")
+            .arg(job_id)
+            .arg("-o")
+            .arg("%T")
+            .stdin(Stdio::null())
+            .output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            // What squeue says about job ids it has already purged
+            if stderr.contains("Invalid job id") {
+                return Ok(QueueState::Gone);
+            }
+            return Ok(QueueState::Unknown(stderr));
+        }
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .map_or(QueueState::Gone, |state| {
+                QueueState::Listed(state.to_string())
+            }))
+    }
+
+    // State, exit code and signal from `sacct`, if it is installed and knows the job
+    fn accounting(&self, job_id: &str) -> Option<(String, i32, i32)> {
+        let output = Command::new(&self.programs.sacct)
+            .arg("-n")
+            .arg("-X")
+            .arg("-P")
+            .arg("-j")
+            .arg(job_id)
+            .arg("-o")
+            .arg("State,ExitCode")
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let line = stdout.lines().find(|line| !line.trim().is_empty())?;
+        let (state, exit_code) = line.trim().split_once('|')?;
+        // ExitCode is `<code>:<signal>`
+        let (code, signal) = exit_code.split_once(':')?;
+        Some((state.to_string(), code.parse().ok()?, signal.parse().ok()?))
+    }
+}
+
+impl Executor for SlurmExecutor {
+    fn label(&self) -> String {
+        TaskLocation::Slurm(self.options.join(" ")).label()
+    }
+
+    // Submitted from this machine onto a shared filesystem, so paths are as local
+    fn config_path(&self, task: &Task) -> PathBuf {
+        LocalExecutor.config_path(task)
+    }
+
+    fn resolve_dir(&self, dir: &Path) -> PathBuf {
+        LocalExecutor.resolve_dir(dir)
+    }
+
+    fn read_config(&self, path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+        LocalExecutor.read_config(path)
+    }
+
+    fn write_config(&self, path: &Path, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+        LocalExecutor.write_config(path, contents)
+    }
+
+    fn config_exists(&self, path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+        LocalExecutor.config_exists(path)
+    }
+
+    fn remove_config(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        LocalExecutor.remove_config(path)
+    }
+
+    fn find_files(
+        &self,
+        dir: &Path,
+        prefix: &str,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        LocalExecutor.find_files(dir, prefix)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        LocalExecutor.read_file(path)
+    }
+
+    fn run(
+        &self,
+        command: &LaunchCommand,
+        log: &SharedLog,
+        control: &SharedControl,
+        timeout: Option<Duration>,
+    ) -> io::Result<ExitStatus> {
+        let working_dir = path::absolute(command.get_working_dir())?;
+        let slurm_dir = working_dir.join(SLURM_DIR);
+        fs::create_dir_all(&slurm_dir)?;
+        let stem = format!(
+            "{}_{}",
+            now_unix_secs(),
+            SUBMISSION_COUNTER.fetch_add(1, Ordering::SeqCst)
+        );
+        let script_path = slurm_dir.join(format!("{}.sbatch", stem));
+        let output_file = slurm_dir.join(format!("{}.out", stem));
+        let exit_file = slurm_dir.join(format!("{}.exit", stem));
+
+        let mut absolute_command = command.clone();
+        absolute_command.set_working_dir(working_dir);
+        fs::write(
+            &script_path,
+            self.batch_script(&absolute_command, &output_file, &exit_file)?,
+        )?;
+
+        let job_id = self.submit(&script_path, log)?;
+        log.lock()
+            .unwrap()
+            .push_line(format!("Submitted Slurm job {}", job_id));
+
+        let mut output = OutputTail::new(output_file);
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+        let mut last_state = String::new();
+        let mut next_poll = Instant::now();
+        let mut scancelled = false;
+        loop {
+            output.drain_into(log);
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                && !control.is_cancelled()
+            {
+                log.lock()
+                    .unwrap()
+                    .push_line("Task exceeded its timeout, stopping it.");
+                control.time_out(CANCEL_GRACE_PERIOD);
+            }
+            if control.is_cancelled() && !scancelled {
+                scancelled = true;
+                self.scancel(&job_id, log);
+            }
+
+            if Instant::now() >= next_poll {
+                next_poll = Instant::now() + self.poll_interval;
+                let state = match self.queue_state(&job_id) {
+                    Ok(QueueState::Listed(state)) => state,
+                    Ok(QueueState::Unknown(error)) => format!("squeue failed ({})", error),
+                    Ok(QueueState::Gone) => {
+                        // squeue can forget a job before it is completely done
+                        match self.accounting(&job_id) {
+                            Some((state, _, _))
+                                if !exit_file.exists() && is_active_state(&state) =>
+                            {
+                                state
+                            }
+                            _ => break, // Finished one way or another
+                        }
+                    }
+                    Err(e) => {
+                        // Nothing would follow the job any more, so don't leave it running
+                        if !scancelled {
+                            self.scancel(&job_id, log);
+                        }
+                        control.set_remote_state(None);
+                        return Err(io::Error::new(
+                            e.kind(),
+                            format!("Could not run squeue for job {}: {}", job_id, e),
+                        ));
+                    }
+                };
+                if state != last_state {
+                    log.lock()
+                        .unwrap()
+                        .push_line(format!("Slurm job {}: {}", job_id, state));
+                    control.set_remote_state(Some(format!("{} #{}", state, job_id)));
+                    last_state = state;
+                }
+            }
+            thread::sleep(WAIT_POLL_INTERVAL);
+        }
+        output.drain_into(log);
+        output.flush_into(log);
+        control.set_remote_state(None);
+
+        // The script's own record is exact; sacct covers jobs killed before writing it
+        if let Some(code) = fs::read_to_string(&exit_file)
+            .ok()
+            .and_then(|text| text.trim().parse::<i32>().ok())
+        {
+            return Ok(ExitStatus::from_raw((code & 0xff) << 8));
+        }
+        match self.accounting(&job_id) {
+            Some((state, code, signal)) if !is_active_state(&state) => {
+                log.lock()
+                    .unwrap()
+                    .push_line(format!("Slurm job {} ended as {}", job_id, state));
+                Ok(accounting_status(&state, code, signal))
+            }
+            _ => Err(io::Error::other(format!(
+                "Slurm job {} left the queue without an exit code",
+                job_id
+            ))),
+        }
+    }
+}
+
+// sacct adds details after the state, e.g. `CANCELLED by 1000`
+fn is_active_state(state: &str) -> bool {
+    let state = state.split_whitespace().next().unwrap_or_default();
+    ACTIVE_STATES.contains(&state)
+}
+
+// Exit status for a finished job's sacct record
+fn accounting_status(state: &str, code: i32, signal: i32) -> ExitStatus {
+    if signal != 0 {
+        ExitStatus::from_raw(signal & 0x7f)
+    } else if code == 0 && state != "COMPLETED" {
+        // e.g. NODE_FAIL or a Slurm-side TIMEOUT, which report no code of their own
+        ExitStatus::from_raw(1 << 8)
+    } else {
+        ExitStatus::from_raw((code & 0xff) << 8)
+    }
+}
+
+// Follows the job's output file as Slurm appends to it
+struct OutputTail {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>, // Bytes after the last newline, held until the line is complete
+}
+
+impl OutputTail {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            partial: vec![],
+        }
+    }
+
+    fn drain_into(&mut self, log: &SharedLog) {
+        // Not there until the job starts
+        let Ok(mut file) = File::open(&self.path) else {
+            return;
+        };
+        if file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return;
+        }
+        let mut bytes = vec![];
+        let Ok(n) = file.read_to_end(&mut bytes) else {
+            return;
+        };
+        self.offset += n as u64;
+        self.partial.extend_from_slice(&bytes);
+
+        let mut log = log.lock().unwrap();
+        while let Some(end) = self.partial.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = self.partial.drain(..=end).collect();
+            log.push_line(
+                String::from_utf8_lossy(&line)
+                    .trim_end_matches(['\n', '\r'])
+                    .to_string(),
+            );
+        }
+    }
+
+    // Whatever is left once the job is gone, even without a final newline
+    fn flush_into(&mut self, log: &SharedLog) {
+        if !self.partial.is_empty() {
+            let line = String::from_utf8_lossy(&self.partial).to_string();
+            log.lock().unwrap().push_line(line);
+            self.partial.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::control::RunControl;
+    use crate::runner::log_buffer::new_shared_log;
+    use std::os::unix::fs::PermissionsExt;
+
+    // Prints the first line of a file of scripted answers, the last one repeating
+    const NEXT_ANSWER: &str =
+        r#"next() { head -n 1 "$1"; [ "$(wc -l < "$1")" -gt 1 ] && sed -i 1d "$1"; true; }"#;
+
+    // Stand-in Slurm commands in a fresh directory. sbatch only runs the job
+    // when a `start` file is there; `squeue` and `sacct` answer from files.
+    fn fake_slurm(name: &str, squeue: &str, sacct: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pushing_launch_slurm_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("squeue_answers"), squeue).unwrap();
+        fs::write(dir.join("sacct_answers"), sacct).unwrap();
+        let d = dir.display();
+        let scripts = [
+            (
+                "sbatch",
+                format!(
+                    "echo \"$*\" > {d}/sbatch_args\nscript=${{!#}}\n\
+                     if [ -e {d}/start ]; then bash \"$script\" > \"${{script%.sbatch}}.out\" 2>&1; fi\n\
+                     echo '42;cluster'"
+                ),
+            ),
+            (
+                "squeue",
+                format!(
+                    "{NEXT_ANSWER}\n[ -e {d}/scancelled ] && answer=GONE || answer=$(next {d}/squeue_answers)\n\
+                     case $answer in\n\
+                     GONE) echo 'slurm_load_jobs error: Invalid job id specified' >&2; exit 1 ;;\n\
+                     FAIL) echo 'slurm_load_jobs error: Socket timed out' >&2; exit 1 ;;\n\
+                     *) echo \"$answer\" ;;\n\
+                     esac"
+                ),
+            ),
+            ("sacct", format!("{NEXT_ANSWER}\nnext {d}/sacct_answers")),
+            ("scancel", format!("touch {d}/scancelled")),
+        ];
+        for (name, body) in scripts {
+            let path = dir.join(name);
+            fs::write(&path, format!("#!/bin/bash\n{}\n", body)).unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        dir
+    }
+
+    fn run_job(
+        dir: &Path,
+        command: &LaunchCommand,
+        control: &SharedControl,
+    ) -> (io::Result<ExitStatus>, Vec<String>) {
+        let executor = SlurmExecutor::with_programs(
+            "-p debug --time=1:00",
+            SlurmPrograms::in_dir(dir),
+            Duration::from_millis(10),
+        );
+        let log = new_shared_log();
+        let result = executor.run(command, &log, control, None);
+        let lines = log
+            .lock()
+            .unwrap()
+            .window(usize::MAX, 0)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        (result, lines)
+    }
+
+    fn shell(dir: &Path, script: &str) -> LaunchCommand {
+        let mut command = LaunchCommand::new("bash", dir.to_path_buf());
+        command.arg("-c").arg(script);
+        command
+    }
+
+    #[test]
+    fn completed_job_reports_its_output_and_exit_code() {
+        let dir = fake_slurm("completed", "PENDING\nRUNNING\nGONE\n", "COMPLETED|0:0\n");
+        fs::write(dir.join("start"), "").unwrap();
+        let (result, lines) = run_job(&dir, &shell(&dir, "echo hello"), &RunControl::new_shared());
+        assert!(result.unwrap().success());
+        assert!(lines.contains(&String::from("Submitted Slurm job 42")));
+        assert!(lines.contains(&String::from("Slurm job 42: PENDING")));
+        assert!(lines.contains(&String::from("Slurm job 42: RUNNING")));
+        assert!(lines.contains(&String::from("hello")));
+        let sbatch_args = fs::read_to_string(dir.join("sbatch_args")).unwrap();
+        assert!(sbatch_args.starts_with("--parsable -p debug --time=1:00 "));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn failed_job_reports_the_exit_code_it_wrote() {
+        let dir = fake_slurm("failed", "RUNNING\nGONE\n", "FAILED|3:0\n");
+        fs::write(dir.join("start"), "").unwrap();
+        let (result, _) = run_job(&dir, &shell(&dir, "exit 3"), &RunControl::new_shared());
+        assert_eq!(result.unwrap().code(), Some(3));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cancelled_job_is_scancelled() {
+        let dir = fake_slurm("cancelled", "PENDING\n", "CANCELLED by 1000|0:0\n");
+        let control = RunControl::new_shared();
+        control.cancel(Duration::from_secs(60));
+        let (result, lines) = run_job(&dir, &shell(&dir, "true"), &control);
+        assert!(dir.join("scancelled").exists());
+        assert!(!result.unwrap().success());
+        assert!(lines.contains(&String::from("$ scancel 42")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn squeue_errors_are_retried() {
+        let dir = fake_slurm(
+            "transient",
+            "RUNNING\nFAIL\nRUNNING\nGONE\n",
+            "COMPLETED|0:0\n",
+        );
+        fs::write(dir.join("start"), "").unwrap();
+        let (result, lines) = run_job(&dir, &shell(&dir, "true"), &RunControl::new_shared());
+        assert!(result.unwrap().success());
+        assert!(lines.iter().any(|line| line.contains("Socket timed out")));
+        assert!(!dir.join("scancelled").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn job_still_completing_in_sacct_is_followed() {
+        // Never wrote an exit file, so only sacct knows how it ended
+        let dir = fake_slurm("completing", "GONE\n", "COMPLETING|0:0\nFAILED|2:0\n");
+        let (result, lines) = run_job(&dir, &shell(&dir, "true"), &RunControl::new_shared());
+        assert_eq!(result.unwrap().code(), Some(2));
+        assert!(lines.contains(&String::from("Slurm job 42: COMPLETING")));
+        assert!(lines.contains(&String::from("Slurm job 42 ended as FAILED")));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn missing_squeue_cancels_the_job() {
+        let dir = fake_slurm("no_squeue", "RUNNING\n", "COMPLETED|0:0\n");
+        fs::remove_file(dir.join("squeue")).unwrap();
+        let (result, _) = run_job(&dir, &shell(&dir, "true"), &RunControl::new_shared());
+        assert!(result.is_err());
+        assert!(dir.join("scancelled").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn paths_with_spaces_are_rejected() {
+        let executor = SlurmExecutor::new("");
+        let command = LaunchCommand::new("true", PathBuf::from("/tmp/my runs"));
+        let result = executor.batch_script(
+            &command,
+            Path::new("/tmp/out.txt"),
+            Path::new("/tmp/exit.txt"),
+        );
+        assert!(result.is_err());
+    }
+}
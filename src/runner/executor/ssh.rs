@@ -0,0 +1,373 @@
+use super::Executor;
+use crate::app::task_config::{Task, TaskLocation};
+use crate::runner::{
+    LaunchCommand, control::SharedControl, log_buffer::SharedLog, run_process_with_output,
+    shell_quote, shell_quote_path,
+};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::time::Duration;
+
+/// How a shell snippet reaches the remote host. `SshTransport` is the real
+/// one; anything else (e.g. plain local bash) can stand in for it.
+pub trait Transport: Send {
+    fn host(&self) -> &str;
+    /// A process that runs `script` with bash on the remote host.
+    fn command(&self, script: &str) -> Command;
+    /// Like `command`, for the long-running task itself.
+    fn session_command(&self, script: &str) -> Command {
+        self.command(script)
+    }
+}
+
+pub struct SshTransport {
+    host: String,
+}
+
+impl SshTransport {
+    pub fn new(host: &str) -> Self {
+        Self {
+            host: String::from(host),
+        }
+    }
+}
+
+impl Transport for SshTransport {
+    fn host(&self) -> &str {
+        &self.host
+    }
+    fn command(&self, script: &str) -> Command {
+        let mut command = Command::new("ssh");
+        command
+            // Never stop to ask for a password, the TUI owns the terminal
+            .arg("-o")
+            .arg("BatchMode=yes")
+            .arg(&self.host)
+            .arg(format!("bash -lc {}", shell_quote(script)));
+        command
+    }
+    fn session_command(&self, script: &str) -> Command {
+        let mut command = Command::new("ssh");
+        command
+            .arg("-o")
+            .arg("BatchMode=yes")
+            // Force a remote tty so the remote task dies with the connection on cancel
+            .arg("-tt")
+            .arg(&self.host)
+            .arg(format!("bash -lc {}", shell_quote(script)));
+        command
+    }
+}
+
+/// Runs tasks on another machine through a `Transport`.
+pub struct SshExecutor<T: Transport> {
+    transport: T,
+}
+
+impl<T: Transport> SshExecutor<T> {
+    pub fn new(transport: T) -> Self {
+        Self { transport }
+    }
+
+    // Runs a short script on the host and returns its stdout, or its stderr as the error
+    fn remote_output(
+        &self,
+        script: &str,
+        what: &str,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let output = self
+            .transport
+            .command(script)
+            .stdin(Stdio::null())
+            .output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} on {} failed: {}",
+                what,
+                self.transport.host(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(output.stdout)
+    }
+
+    // `cd` into the task's directory on the remote side, then run the command
+    pub fn remote_script(&self, command: &LaunchCommand) -> String {
+        format!(
+            "cd {} && {}",
+            shell_quote_path(&command.get_working_dir().to_string_lossy()),
+            command.to_shell_string()
+        )
+    }
+}
+
+impl<T: Transport> Executor for SshExecutor<T> {
+    fn label(&self) -> String {
+        TaskLocation::Ssh(self.transport.host().to_string()).label()
+    }
+
+    // Hydra needs an absolute path, so a relative directory is resolved on the host
+    fn config_path(&self, task: &Task) -> PathBuf {
+        self.resolve_dir(Path::new(task.get_directory()))
+            .join(task.get_config_file())
+    }
+
+    fn resolve_dir(&self, dir: &Path) -> PathBuf {
+        if dir.is_absolute() {
+            return dir.to_path_buf();
+        }
+        let typed = dir.to_string_lossy();
+        let script = format!("cd {} && pwd", shell_quote_path(&typed));
+        // Left as typed if that fails, writing the config then reports the error
+        self.remote_output(&script, &format!("Resolving {}", typed))
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output).trim().to_string())
+            .filter(|resolved| resolved.starts_with('/'))
+            .map_or_else(|| dir.to_path_buf(), PathBuf::from)
+    }
+
+    fn read_config(&self, path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+        let path = path.to_string_lossy();
+        let output = self
+            .transport
+            .command(&format!("cat {}", shell_quote(&path)))
+            .stdin(Stdio::null())
+            .output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "Reading {} on {} failed: {}",
+                path,
+                self.transport.host(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    fn write_config(&self, path: &Path, contents: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_path = super::tmp_path_for(path);
+        let path = path.to_string_lossy();
+        // Same write-then-rename as locally, `mv` within a directory is atomic
+        let script = format!(
+            "cat > {tmp} && mv -f {tmp} {path}",
+            tmp = shell_quote(&tmp_path.to_string_lossy()),
+            path = shell_quote(&path)
+        );
+        let mut child = self
+            .transport
+            .command(&script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(contents.as_bytes())?;
+        } // stdin dropped here, which ends `cat`
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(format!(
+                "Writing {} on {} failed: {}",
+                path,
+                self.transport.host(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn config_exists(&self, path: &Path) -> Result<bool, Box<dyn std::error::Error>> {
+        let path = path.to_string_lossy();
+        // Exits 2 when the file is missing; ssh itself fails with 255
+        let script = format!("test -e {} || exit 2", shell_quote(&path));
+        let status = self
+            .transport
+            .command(&script)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()?;
+        match status.code() {
+            Some(0) => Ok(true),
+            Some(2) => Ok(false),
+            _ => Err(format!("Checking {} on {} failed", path, self.transport.host()).into()),
+        }
+    }
+
+    fn remove_config(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let path = path.to_string_lossy();
+        self.remote_output(
+            &format!("rm -f {}", shell_quote(&path)),
+            &format!("Removing {}", path),
+        )?;
+        Ok(())
+    }
+
+    fn find_files(
+        &self,
+        dir: &Path,
+        prefix: &str,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let dir = dir.to_string_lossy();
+        let script = format!(
+            "find {} -type f -name {}",
+            shell_quote_path(&dir),
+            shell_quote(&format!("{}*", prefix))
+        );
+        let output = self.remote_output(&script, &format!("Searching {}", dir))?;
+        let mut found: Vec<PathBuf> = String::from_utf8_lossy(&output)
+            .lines()
+            .map(PathBuf::from)
+            .collect();
+        found.sort();
+        Ok(found)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let path = path.to_string_lossy();
+        self.remote_output(
+            &format!("cat {}", shell_quote_path(&path)),
+            &format!("Reading {}", path),
+        )
+    }
+
+    fn run(
+        &self,
+        command: &LaunchCommand,
+        log: &SharedLog,
+        control: &SharedControl,
+        timeout: Option<Duration>,
+    ) -> io::Result<ExitStatus> {
+        let script = self.remote_script(command);
+        let display = format!("[{}] {}", self.transport.host(), script);
+        run_process_with_output(
+            self.transport.session_command(&script),
+            &display,
+            log,
+            control,
+            timeout,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::control::RunControl;
+    use crate::runner::log_buffer::new_shared_log;
+    use std::fs;
+
+    // Runs the "remote" script with local bash, so no ssh server is needed
+    struct LocalBash;
+
+    impl Transport for LocalBash {
+        fn host(&self) -> &str {
+            "localhost"
+        }
+        fn command(&self, script: &str) -> Command {
+            let mut command = Command::new("bash");
+            command.arg("-c").arg(script);
+            command
+        }
+    }
+
+    // A fresh directory with a space in its name, to catch missing quotes
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "pushing_launch ssh {} {}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn run(command: &LaunchCommand) -> (ExitStatus, Vec<String>) {
+        let log = new_shared_log();
+        let status = SshExecutor::new(LocalBash)
+            .run(command, &log, &RunControl::new_shared(), None)
+            .unwrap();
+        let lines = log
+            .lock()
+            .unwrap()
+            .window(usize::MAX, 0)
+            .into_iter()
+            .map(String::from)
+            .collect();
+        (status, lines)
+    }
+
+    #[test]
+    fn remote_script_quotes_the_directory_and_arguments() {
+        let mut command = LaunchCommand::new("echo", PathBuf::from("/tmp/my dir"));
+        command.arg("it's").env("KEY", "a b");
+        let script = SshExecutor::new(LocalBash).remote_script(&command);
+        assert_eq!(script, "cd '/tmp/my dir' && KEY='a b' echo 'it'\\''s'");
+    }
+
+    #[test]
+    fn run_goes_through_the_quoted_script() {
+        let dir = scratch_dir("run");
+        let mut command = LaunchCommand::new("bash", dir.clone());
+        command
+            .arg("-c")
+            .arg("printf '%s|%s\\n' \"$PWD\" \"$1\"")
+            .arg("bash")
+            .arg("it's here");
+        let (status, lines) = run(&command);
+        assert!(status.success());
+        assert!(lines.contains(&format!("{}|it's here", dir.display())));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn run_expands_a_home_relative_directory() {
+        let command = LaunchCommand::new("pwd", PathBuf::from("~/."));
+        let script = SshExecutor::new(LocalBash).remote_script(&command);
+        assert_eq!(script, "cd \"$HOME\"/. && pwd");
+        let (status, lines) = run(&command);
+        assert!(status.success());
+        let home = fs::canonicalize(std::env::var("HOME").unwrap()).unwrap();
+        assert_eq!(fs::canonicalize(&lines[1]).unwrap(), home);
+    }
+
+    #[test]
+    fn run_passes_the_exit_status_through() {
+        let mut command = LaunchCommand::new("bash", std::env::temp_dir());
+        command.arg("-c").arg("exit 3");
+        let (status, _) = run(&command);
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[test]
+    fn config_round_trips_through_the_transport() {
+        let dir = scratch_dir("config");
+        let path = dir.join("agent's config.yaml");
+        let executor = SshExecutor::new(LocalBash);
+        let contents = "seed: 42\nname: 'a b'\n";
+        executor.write_config(&path, contents).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+        assert_eq!(executor.read_config(&path).unwrap(), contents);
+        // The temporary file is renamed away, not left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        assert!(executor.read_config(&dir.join("missing.yaml")).is_err());
+        assert!(executor.config_exists(&path).unwrap());
+        executor.remove_config(&path).unwrap();
+        assert!(!executor.config_exists(&path).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn config_path_resolves_a_relative_directory_on_the_host() {
+        let mut task = Task::new();
+        task.set_directory(String::from("src"));
+        task.set_config_file(String::from("agent.yaml"));
+        let expected = std::env::current_dir().unwrap().join("src/agent.yaml");
+        assert_eq!(SshExecutor::new(LocalBash).config_path(&task), expected);
+    }
+}
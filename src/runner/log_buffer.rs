@@ -0,0 +1,111 @@
+use super::progress::{TrainingProgress, strip_ansi};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+// Enough scrollback for a long training run without growing forever
+pub const DEFAULT_LOG_CAPACITY: usize = 5000;
+
+/// Fixed-size ring buffer holding the most recent output lines of a task.
+#[derive(Debug)]
+pub struct LogBuffer {
+    lines: VecDeque<String>,
+    capacity: usize,
+    file_path: Option<PathBuf>, // Full copy of the output, kept after the ring drops lines
+    file: Option<File>,         // Open only while the task can still write to it
+    wandb_url: Option<String>,  // W&B run page, once the run has printed it
+    progress: TrainingProgress, // Training curve parsed from the output
+}
+
+pub type SharedLog = Arc<Mutex<LogBuffer>>;
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+            file_path: None,
+            file: None,
+            wandb_url: None,
+            progress: TrainingProgress::default(),
+        }
+    }
+    /// Also appends every line to `path`, creating its parent directories.
+    pub fn tee_to_file(&mut self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = File::options().create(true).append(true).open(path)?;
+        self.file_path = Some(path.to_path_buf());
+        self.file = Some(file);
+        Ok(())
+    }
+    /// Stops copying lines to the file, once the task is done with its log.
+    pub fn close_file(&mut self) {
+        self.file = None;
+    }
+    pub fn get_file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+    pub fn get_wandb_url(&self) -> Option<&str> {
+        self.wandb_url.as_deref()
+    }
+    pub fn get_progress(&self) -> &TrainingProgress {
+        &self.progress
+    }
+    pub fn push_line<S: Into<String>>(&mut self, line: S) {
+        // RSL-RL prints in bold; the pane would show the escapes as `[1m` junk
+        let line = strip_ansi(&line.into());
+        self.progress.parse_line(&line);
+        if self.wandb_url.is_none() {
+            self.wandb_url = parse_wandb_run_url(&line).map(String::from);
+        }
+        if let Some(file) = self.file.as_mut() {
+            let _ = writeln!(file, "{}", line);
+        }
+        if self.capacity == 0 {
+            return;
+        }
+        // Drop the oldest line once full
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(line);
+    }
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns up to `height` lines ending `scroll` lines above the newest one.
+    pub fn window(&self, height: usize, scroll: usize) -> Vec<&str> {
+        let end = self.lines.len().saturating_sub(scroll);
+        let start = end.saturating_sub(height);
+        self.lines
+            .range(start..end)
+            .map(|line| line.as_str())
+            .collect()
+    }
+}
+
+/// The run URL from wandb's `View run at https://wandb.ai/...` line.
+pub fn parse_wandb_run_url(line: &str) -> Option<&str> {
+    if !line.contains("wandb") || !line.contains("View run") {
+        return None;
+    }
+    let start = line.find("https://")?;
+    let url = &line[start..];
+    // Ends at whitespace or a colour escape, in case wandb thinks it has a terminal
+    let end = url
+        .find(|c: char| c.is_whitespace() || c == '\x1b')
+        .unwrap_or(url.len());
+    Some(&url[..end])
+}
+
+pub fn new_shared_log() -> SharedLog {
+    Arc::new(Mutex::new(LogBuffer::new(DEFAULT_LOG_CAPACITY)))
+}
@@ -0,0 +1,297 @@
+pub mod backup;
+pub mod checkpoints;
+pub mod control;
+pub mod executor;
+pub mod log_buffer;
+pub mod pool;
+pub mod progress;
+pub mod tensorboard;
+use crate::app::task_config::{PythonEnv, Task, TaskKind};
+use control::{CANCEL_GRACE_PERIOD, SharedControl};
+use log_buffer::SharedLog;
+use std::io::{self, BufRead, BufReader, Read};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Isaac Lab's launcher script, found at the root of every Isaac Lab checkout
+pub const ISAACLAB_LAUNCHER: &str = "./isaaclab.sh";
+
+// Where Isaac Lab's scripts log runs and checkpoints, under the task's directory
+pub const LOGS_DIR: &str = "logs";
+
+// How often a running task is checked for exit, cancellation and timeout
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A fully resolved launch: what to run and where to run it from.
+#[derive(Debug, Clone)]
+pub struct LaunchCommand {
+    setup: Vec<String>, // Shell steps run first, e.g. activating a conda env
+    program: String,
+    args: Vec<String>,
+    env: Vec<(String, String)>, // Set for the command only, e.g. CUDA_VISIBLE_DEVICES
+    working_dir: PathBuf,
+}
+
+impl LaunchCommand {
+    pub fn new<S: Into<String>>(program: S, working_dir: PathBuf) -> Self {
+        Self {
+            setup: vec![],
+            program: program.into(),
+            args: vec![],
+            env: vec![],
+            working_dir,
+        }
+    }
+    pub fn arg<S: Into<String>>(&mut self, arg: S) -> &mut Self {
+        self.args.push(arg.into());
+        self
+    }
+    /// Adds a shell step (taken verbatim) that must succeed before the program runs.
+    pub fn setup<S: Into<String>>(&mut self, step: S) -> &mut Self {
+        self.setup.push(step.into());
+        self
+    }
+    pub fn env<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) -> &mut Self {
+        self.env.push((key.into(), value.into()));
+        self
+    }
+    pub fn get_setup(&self) -> &[String] {
+        &self.setup
+    }
+    pub fn get_program(&self) -> &str {
+        &self.program
+    }
+    pub fn get_args(&self) -> &[String] {
+        &self.args
+    }
+    pub fn get_env(&self) -> &[(String, String)] {
+        &self.env
+    }
+    pub fn get_working_dir(&self) -> &Path {
+        &self.working_dir
+    }
+    pub fn set_working_dir(&mut self, working_dir: PathBuf) {
+        self.working_dir = working_dir;
+    }
+
+    /// The command as a single line that can be pasted into bash, with its
+    /// setup steps and `KEY=value` environment assignments in front.
+    pub fn to_shell_string(&self) -> String {
+        let mut line = String::new();
+        for step in &self.setup {
+            line.push_str(step);
+            line.push_str(" && ");
+        }
+        for (key, value) in &self.env {
+            line.push_str(&format!("{}={} ", key, shell_quote(value)));
+        }
+        line.push_str(&shell_quote(&self.program));
+        for arg in &self.args {
+            line.push(' ');
+            line.push_str(&shell_quote(arg));
+        }
+        line
+    }
+
+    // Run through bash (like the rest of the tool) from the task's directory
+    pub fn to_command(&self) -> Command {
+        let mut command = Command::new("bash");
+        command
+            .arg("-c")
+            .arg(self.to_shell_string())
+            .current_dir(&self.working_dir);
+        command
+    }
+}
+
+/// Runs the command to completion, streaming stdout and stderr into `log`
+/// instead of the terminal (which ratatui owns while a task runs). A task
+/// still running after `timeout` is stopped like a cancelled one.
+pub fn run_with_output(
+    command: &LaunchCommand,
+    log: &SharedLog,
+    control: &SharedControl,
+    timeout: Option<Duration>,
+) -> io::Result<ExitStatus> {
+    run_process_with_output(
+        command.to_command(),
+        &command.to_shell_string(),
+        log,
+        control,
+        timeout,
+    )
+}
+
+/// Same as `run_with_output` for an already assembled process, e.g. one
+/// wrapped in ssh by an executor. `display` is what gets echoed to the log.
+pub fn run_process_with_output(
+    mut process: Command,
+    display: &str,
+    log: &SharedLog,
+    control: &SharedControl,
+    timeout: Option<Duration>,
+) -> io::Result<ExitStatus> {
+    let mut child = process
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        // Own process group, so cancelling reaches everything the task starts
+        .process_group(0)
+        .spawn()?;
+    control.set_pid(Some(child.id()));
+
+    log.lock().unwrap().push_line(format!("$ {}", display));
+
+    // One reader per pipe so a chatty stderr can't block stdout (or vice versa)
+    let mut readers = vec![];
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(pipe_into_log(stdout, log));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(pipe_into_log(stderr, log));
+    }
+
+    // Poll rather than block in wait() so the task can be stopped at its deadline
+    // A timeout too far out for `Instant` (e.g. from a hand-edited queue) means none
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+    let status = loop {
+        match control.reap(&mut child) {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => {}
+            Err(e) => break Err(e),
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) && !control.is_cancelled() {
+            log.lock()
+                .unwrap()
+                .push_line("Task exceeded its timeout, stopping it.");
+            control.time_out(CANCEL_GRACE_PERIOD);
+        }
+        thread::sleep(WAIT_POLL_INTERVAL);
+    };
+    control.release_group(WAIT_POLL_INTERVAL);
+    for reader in readers {
+        let _ = reader.join();
+    }
+    status
+}
+
+fn pipe_into_log<R: Read + Send + 'static>(pipe: R, log: &SharedLog) -> thread::JoinHandle<()> {
+    let log = SharedLog::clone(log);
+    thread::spawn(move || {
+        let mut reader = BufReader::new(pipe);
+        let mut bytes = vec![];
+        // Read raw bytes so a stray non-UTF-8 byte doesn't end the stream
+        while let Ok(n) = reader.read_until(b'\n', &mut bytes) {
+            if n == 0 {
+                break;
+            }
+            let line = String::from_utf8_lossy(&bytes);
+            log.lock()
+                .unwrap()
+                .push_line(line.trim_end_matches(['\n', '\r']));
+            bytes.clear();
+        }
+    })
+}
+
+/// Builds the `isaaclab.sh -p <script> --task <env>` invocation for a task.
+///
+/// When `yaml_path` is given the written config is handed to Hydra as the
+/// primary config, so the edits made in the YAML page are what the run uses.
+pub fn build_launch_command(task: &Task, yaml_path: Option<&Path>) -> LaunchCommand {
+    let mut command = LaunchCommand::new(ISAACLAB_LAUNCHER, PathBuf::from(task.get_directory()));
+    command
+        .arg("-p")
+        .arg(task.get_launch_script())
+        .arg("--task")
+        .arg(task.get_environment())
+        .arg("--headless");
+    if let Some(resume) = task.get_resume() {
+        // --load_run is looked up under the experiment, which may not be the env's default
+        if !resume.get_experiment().is_empty() {
+            command
+                .arg("--experiment_name")
+                .arg(resume.get_experiment());
+        }
+        command
+            .arg("--resume")
+            .arg("--load_run")
+            .arg(resume.get_load_run())
+            .arg("--checkpoint")
+            .arg(resume.get_checkpoint());
+    }
+    if let TaskKind::Play { checkpoint, video } = task.get_kind() {
+        command.arg("--checkpoint").arg(checkpoint);
+        if *video {
+            // play.py turns on the cameras it needs for recording by itself
+            command.arg("--video");
+        }
+    }
+    match task.get_python_env() {
+        Some(PythonEnv::Conda(name)) => {
+            // `conda activate` only works once the shell hook is loaded
+            command.setup("eval \"$(conda shell.bash hook)\"");
+            command.setup(format!("conda activate {}", shell_quote(name)));
+        }
+        Some(PythonEnv::Venv(path)) => {
+            command.setup(format!("source {}/bin/activate", shell_quote_path(path)));
+        }
+        None => {}
+    }
+    if let Some(wandb) = task.get_wandb() {
+        command.arg("--logger").arg("wandb");
+        if let Some(project) = wandb.get_project() {
+            command.arg("--log_project_name").arg(project);
+        }
+        let wandb_env = [
+            ("WANDB_PROJECT", wandb.get_project()),
+            ("WANDB_ENTITY", wandb.get_entity()),
+            ("WANDB_NAME", Some(task.get_task_name())),
+        ];
+        for (key, value) in wandb_env {
+            // The task's own env vars win over these defaults
+            if let Some(value) = value
+                && !task.get_env_vars().contains_key(key)
+            {
+                command.env(key, value);
+            }
+        }
+    }
+    for (key, value) in task.get_env_vars() {
+        command.env(key.as_str(), value.as_str());
+    }
+
+    if let Some(yaml_path) = yaml_path {
+        if let Some(parent) = yaml_path.parent() {
+            command.arg("--config-path").arg(parent.to_string_lossy());
+        }
+        if let Some(stem) = yaml_path.file_stem() {
+            command.arg("--config-name").arg(stem.to_string_lossy());
+        }
+    }
+    command
+}
+
+// Single-quote anything that bash could otherwise split or expand
+pub fn shell_quote(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if is_plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+// Like `shell_quote`, but keeps a leading `~/` working by going through $HOME
+fn shell_quote_path(path: &str) -> String {
+    match path.strip_prefix("~/") {
+        Some(rest) => format!("\"$HOME\"/{}", shell_quote(rest)),
+        None => shell_quote(path),
+    }
+}
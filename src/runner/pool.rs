@@ -0,0 +1,77 @@
+use crate::app::task_config::TaskStatus;
+use std::thread::{self, JoinHandle};
+
+pub const DEFAULT_MAX_WORKERS: usize = 1;
+
+// One running task: its thread, which returns what the task ended as
+struct WorkerSlot {
+    task_id: usize,
+    handle: JoinHandle<TaskStatus>,
+}
+
+/// Runs up to `max_workers` tasks at once, each on its own thread.
+pub struct WorkerPool {
+    slots: Vec<Option<WorkerSlot>>,
+}
+
+impl WorkerPool {
+    pub fn new(max_workers: usize) -> Self {
+        let mut slots = vec![];
+        // Always at least one slot, otherwise nothing would ever run
+        slots.resize_with(max_workers.max(1), || None);
+        Self { slots }
+    }
+    pub fn get_max_workers(&self) -> usize {
+        self.slots.len()
+    }
+    pub fn running_count(&self) -> usize {
+        self.slots.iter().filter(|slot| slot.is_some()).count()
+    }
+    pub fn has_free_slot(&self) -> bool {
+        self.slots.iter().any(|slot| slot.is_none())
+    }
+
+    /// Starts `job` for a task in a free slot. Returns false when every slot is busy.
+    pub fn spawn<F>(&mut self, task_id: usize, job: F) -> bool
+    where
+        F: FnOnce() -> TaskStatus + Send + 'static,
+    {
+        let Some(free_slot) = self.slots.iter_mut().find(|slot| slot.is_none()) else {
+            return false;
+        };
+        let handle = thread::spawn(job);
+        *free_slot = Some(WorkerSlot { task_id, handle });
+        true
+    }
+
+    /// Frees the slots of finished workers and returns what each task ended as.
+    /// A worker that panicked counts as a task that failed to run.
+    pub fn collect_finished(&mut self) -> Vec<(usize, TaskStatus)> {
+        let mut finished = vec![];
+        for slot in self.slots.iter_mut() {
+            if !slot
+                .as_ref()
+                .is_some_and(|worker| worker.handle.is_finished())
+            {
+                continue;
+            }
+            if let Some(worker) = slot.take() {
+                // Already returned, so this doesn't block
+                let outcome = worker.handle.join().unwrap_or(TaskStatus::Failed(-1));
+                finished.push((worker.task_id, outcome));
+            }
+        }
+        finished
+    }
+
+    // Waits for every running task, used when the app shuts down
+    pub fn join_all(&mut self) {
+        for slot in self.slots.iter_mut() {
+            if let Some(worker) = slot.take()
+                && let Err(e) = worker.handle.join()
+            {
+                eprintln!("Thread panicked: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::VecDeque;
+
+// Most iterations kept per run; a long run still fits and memory stays bounded
+pub const MAX_PROGRESS_POINTS: usize = 20_000;
+
+/// One RSL-RL "Learning iteration" block. Fields stay `None` until the
+/// block's line for them has been seen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrainingPoint {
+    pub iteration: u64,
+    pub mean_reward: Option<f64>,
+    pub episode_length: Option<f64>,
+    pub fps: Option<f64>, // Steps per second, from the "Computation" line
+}
+
+/// Training curve of one run, parsed from its output line by line.
+#[derive(Debug, Default)]
+pub struct TrainingProgress {
+    points: VecDeque<TrainingPoint>,
+}
+
+impl TrainingProgress {
+    /// Copies of the newest `count` points, oldest first.
+    pub fn recent(&self, count: usize) -> Vec<TrainingPoint> {
+        let start = self.points.len().saturating_sub(count);
+        self.points.range(start..).copied().collect()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+    pub fn last(&self) -> Option<&TrainingPoint> {
+        self.points.back()
+    }
+
+    /// Picks out the lines of RSL-RL's per-iteration block, e.g.
+    /// `Learning iteration 12/1500`, `Computation: 45123 steps/s (...)`,
+    /// `Mean reward: 12.34` and `Mean episode length: 250.00`.
+    pub fn parse_line(&mut self, line: &str) {
+        // The iteration header is printed in bold
+        let line = strip_ansi(line);
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("Learning iteration ") {
+            let iteration = rest.split('/').next().unwrap_or_default().trim();
+            if let Ok(iteration) = iteration.parse() {
+                if self.points.len() == MAX_PROGRESS_POINTS {
+                    self.points.pop_front();
+                }
+                self.points.push_back(TrainingPoint {
+                    iteration,
+                    mean_reward: None,
+                    episode_length: None,
+                    fps: None,
+                });
+            }
+            return;
+        }
+
+        // Everything else belongs to the block of the latest iteration
+        let Some(point) = self.points.back_mut() else {
+            return;
+        };
+        let Some((name, value)) = line.split_once(':') else {
+            return;
+        };
+        let number = value
+            .split_whitespace()
+            .next()
+            .and_then(|number| number.parse::<f64>().ok());
+        match name.trim() {
+            "Mean reward" => point.mean_reward = number,
+            "Mean episode length" => point.episode_length = number,
+            "Computation" => point.fps = number,
+            _ => {}
+        }
+    }
+}
+
+/// Drops `ESC [ ... <letter>` colour/style sequences.
+pub fn strip_ansi(line: &str) -> String {
+    let mut plain = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        plain.push(c);
+    }
+    plain
+}
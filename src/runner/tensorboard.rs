@@ -0,0 +1,210 @@
+use super::executor::Executor;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+// TensorBoard names every event file `events.out.tfevents.<time>.<host>...`
+pub const EVENT_FILE_PREFIX: &str = "events.out.tfevents.";
+
+// TensorProto dtypes that hold a plottable scalar
+const DT_FLOAT: u64 = 1;
+const DT_DOUBLE: u64 = 2;
+
+/// `(step, value)` points of one scalar tag, in step order.
+pub type ScalarSeries = Vec<(i64, f64)>;
+
+/// A directory holding event files, i.e. one TensorBoard run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventRun {
+    label: String, // Directory relative to the searched one, as TensorBoard names runs
+    files: Vec<PathBuf>,
+}
+
+impl EventRun {
+    pub fn get_label(&self) -> &str {
+        &self.label
+    }
+    pub fn get_files(&self) -> &[PathBuf] {
+        &self.files
+    }
+}
+
+/// Every run with event files under `logs_dir`, wherever the executor keeps them.
+pub fn find_event_runs(
+    executor: &dyn Executor,
+    logs_dir: &Path,
+) -> Result<Vec<EventRun>, Box<dyn std::error::Error>> {
+    let mut runs: BTreeMap<PathBuf, Vec<PathBuf>> = BTreeMap::new();
+    for file in executor.find_files(logs_dir, EVENT_FILE_PREFIX)? {
+        let dir = file.parent().unwrap_or(logs_dir).to_path_buf();
+        runs.entry(dir).or_default().push(file);
+    }
+    Ok(runs
+        .into_iter()
+        .map(|(dir, files)| {
+            let label = match dir.strip_prefix(logs_dir) {
+                Ok(relative) if relative.as_os_str().is_empty() => String::from("."),
+                Ok(relative) => relative.display().to_string(),
+                Err(_) => dir.display().to_string(),
+            };
+            EventRun { label, files }
+        })
+        .collect())
+}
+
+/// All scalar tags of a run, merged across its event files (one per restart).
+pub fn load_scalars(
+    executor: &dyn Executor,
+    run: &EventRun,
+) -> Result<BTreeMap<String, ScalarSeries>, Box<dyn std::error::Error>> {
+    let mut scalars: BTreeMap<String, ScalarSeries> = BTreeMap::new();
+    for file in run.get_files() {
+        for (tag, points) in parse_event_file(&executor.read_file(file)?) {
+            scalars.entry(tag).or_default().extend(points);
+        }
+    }
+    for points in scalars.values_mut() {
+        points.sort_by_key(|(step, _)| *step);
+    }
+    Ok(scalars)
+}
+
+/// Scalars in the TFRecord-framed `Event` protos of one event file. A
+/// record cut short at the end, as in the file of a run still writing it,
+/// is ignored. The CRCs are not checked.
+pub fn parse_event_file(bytes: &[u8]) -> BTreeMap<String, ScalarSeries> {
+    let mut scalars: BTreeMap<String, ScalarSeries> = BTreeMap::new();
+    let mut rest = bytes;
+    // Each record is: u64 length, u32 CRC of the length, data, u32 CRC of the data
+    while rest.len() >= 12 {
+        let len = u64::from_le_bytes(rest[..8].try_into().unwrap()) as usize;
+        let Some(record_end) = len.checked_add(16).filter(|&end| end <= rest.len()) else {
+            break;
+        };
+        parse_event(&rest[12..12 + len], &mut scalars);
+        rest = &rest[record_end..];
+    }
+    scalars
+}
+
+// Event: step = 2, summary = 5
+fn parse_event(event: &[u8], scalars: &mut BTreeMap<String, ScalarSeries>) {
+    let mut step = 0;
+    let mut values = vec![];
+    for (field, value) in ProtoFields::new(event) {
+        match (field, value) {
+            (2, ProtoValue::Varint(n)) => step = n as i64,
+            (5, ProtoValue::Bytes(summary)) => {
+                // Summary: repeated Value value = 1
+                for (field, value) in ProtoFields::new(summary) {
+                    if let (1, ProtoValue::Bytes(value)) = (field, value)
+                        && let Some(scalar) = parse_summary_value(value)
+                    {
+                        values.push(scalar);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    for (tag, value) in values {
+        scalars.entry(tag).or_default().push((step, value));
+    }
+}
+
+// Summary.Value: tag = 1, simple_value = 2, tensor = 8
+fn parse_summary_value(value: &[u8]) -> Option<(String, f64)> {
+    let mut tag = None;
+    let mut number = None;
+    for (field, value) in ProtoFields::new(value) {
+        match (field, value) {
+            (1, ProtoValue::Bytes(text)) => tag = Some(String::from_utf8_lossy(text).to_string()),
+            (2, ProtoValue::Fixed32(bits)) => number = Some(f32::from_bits(bits) as f64),
+            (8, ProtoValue::Bytes(tensor)) => number = parse_scalar_tensor(tensor),
+            _ => {}
+        }
+    }
+    Some((tag?, number?))
+}
+
+// TensorProto: dtype = 1, tensor_content = 4, float_val = 5, double_val = 6.
+// TF2 summaries store scalars this way; only single float or double values count.
+fn parse_scalar_tensor(tensor: &[u8]) -> Option<f64> {
+    let mut dtype = 0;
+    let mut numbers = vec![];
+    for (field, value) in ProtoFields::new(tensor) {
+        match (field, value) {
+            (1, ProtoValue::Varint(n)) => dtype = n,
+            (4, ProtoValue::Bytes(content)) => numbers.push(content.to_vec()),
+            (5, ProtoValue::Fixed32(bits)) => numbers.push(bits.to_le_bytes().to_vec()),
+            (5, ProtoValue::Bytes(packed)) => numbers.push(packed.to_vec()),
+            (6, ProtoValue::Fixed64(bits)) => numbers.push(bits.to_le_bytes().to_vec()),
+            (6, ProtoValue::Bytes(packed)) => numbers.push(packed.to_vec()),
+            _ => {}
+        }
+    }
+    let bytes = numbers.concat();
+    match (dtype, bytes.len()) {
+        (DT_FLOAT, 4) => Some(f32::from_le_bytes(bytes.try_into().ok()?) as f64),
+        (DT_DOUBLE, 8) => Some(f64::from_le_bytes(bytes.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ProtoValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32(u32),
+}
+
+// Walks the (field number, value) pairs of a protobuf message; stops at anything malformed
+struct ProtoFields<'a> {
+    rest: &'a [u8],
+}
+
+impl<'a> ProtoFields<'a> {
+    fn new(message: &'a [u8]) -> Self {
+        Self { rest: message }
+    }
+
+    fn varint(&mut self) -> Option<u64> {
+        let mut n = 0u64;
+        for (i, byte) in self.rest.iter().enumerate().take(10) {
+            n |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.rest = &self.rest[i + 1..];
+                return Some(n);
+            }
+        }
+        None
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.rest.len() {
+            return None;
+        }
+        let (taken, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(taken)
+    }
+}
+
+impl<'a> Iterator for ProtoFields<'a> {
+    type Item = (u64, ProtoValue<'a>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.varint()?;
+        let value = match key & 0x7 {
+            0 => ProtoValue::Varint(self.varint()?),
+            1 => ProtoValue::Fixed64(u64::from_le_bytes(self.take(8)?.try_into().ok()?)),
+            2 => {
+                let len = usize::try_from(self.varint()?).ok()?;
+                ProtoValue::Bytes(self.take(len)?)
+            }
+            5 => ProtoValue::Fixed32(u32::from_le_bytes(self.take(4)?.try_into().ok()?)),
+            _ => return None, // Groups are long deprecated and never written by TensorBoard
+        };
+        Some((key >> 3, value))
+    }
+}
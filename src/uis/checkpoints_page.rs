@@ -0,0 +1,103 @@
+use super::{Mainpage, highlight};
+use ratatui::{
+    Terminal,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+pub fn render_checkpoints_page_ui<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    mp_struct: &Mainpage,
+) {
+    terminal
+        .draw(|f| {
+            let size = f.area();
+
+            let layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+                .split(size);
+            let right_chunk = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length(5)].as_ref())
+                .split(layout[1]);
+
+            // Only the focused list's selection stands out
+            let files_focused = *mp_struct.get_checkpoint_files_focused();
+            // --- Left side (runs, newest first) ---
+            let runs = mp_struct.get_checkpoint_runs();
+            let run_items: Vec<ListItem> = runs
+                .iter()
+                .map(|run| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(format!(" {}", run.label())),
+                        Span::styled(
+                            format!("  ({})", run.get_checkpoints().len()),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                    ]))
+                })
+                .collect();
+            let mut run_list_state = ListState::default();
+            if !runs.is_empty() {
+                run_list_state.select(Some(mp_struct.get_checkpoint_run_selection()));
+            }
+            let run_list = List::new(run_items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Runs (Esc to go back)"),
+                )
+                .highlight_symbol(" > ")
+                .highlight_style(highlight(!files_focused));
+            f.render_stateful_widget(run_list, layout[0], &mut run_list_state);
+
+            // --- Right side (the selected run's checkpoints, latest first) ---
+            let checkpoints = mp_struct
+                .get_selected_checkpoint_run()
+                .map(|run| run.get_checkpoints())
+                .unwrap_or_default();
+            let checkpoint_items: Vec<ListItem> = checkpoints
+                .iter()
+                .map(|checkpoint| ListItem::new(format!(" {}", checkpoint)))
+                .collect();
+            let mut checkpoint_list_state = ListState::default();
+            if !checkpoints.is_empty() {
+                checkpoint_list_state.select(Some(mp_struct.get_checkpoint_selection()));
+            }
+            let checkpoint_list = List::new(checkpoint_items)
+                .block(Block::default().borders(Borders::ALL).title(
+                    "Checkpoints (Tab: switch list, Enter: resume, p: play, v: play with video)",
+                ))
+                .highlight_symbol(" > ")
+                .highlight_style(highlight(files_focused));
+            f.render_stateful_widget(checkpoint_list, right_chunk[0], &mut checkpoint_list_state);
+
+            let mut info_lines = vec![];
+            if let Some((run, checkpoint)) = mp_struct.get_selected_checkpoint() {
+                info_lines.push(Line::from(vec![
+                    Span::styled("Resume args: ", Style::default().fg(Color::Cyan)),
+                    Span::raw(format!(
+                        "--experiment_name {} --resume --load_run {} --checkpoint {}",
+                        run.get_experiment(),
+                        run.get_run(),
+                        checkpoint
+                    )),
+                ]));
+            }
+            let message = mp_struct.get_checkpoints_message();
+            if !message.is_empty() {
+                info_lines.push(Line::from(Span::styled(
+                    message.to_string(),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            let info = Paragraph::new(info_lines)
+                .wrap(Wrap { trim: false })
+                .block(Block::default().borders(Borders::ALL).title("Selected"));
+            f.render_widget(info, right_chunk[1]);
+        })
+        .unwrap();
+}
@@ -0,0 +1,118 @@
+// Unchanged lines shown around each change, like `diff -u`
+pub const DIFF_CONTEXT: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line-based unified diff of `old` against `new`, including the `---`/`+++`
+/// header. Empty when the two texts have the same lines.
+pub fn unified_diff(old: &str, new: &str, old_label: &str, new_label: &str) -> Vec<String> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let edits = line_edits(&old_lines, &new_lines);
+
+    let changes: Vec<usize> = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Same(_)))
+        .map(|(i, _)| i)
+        .collect();
+    if changes.is_empty() {
+        return vec![];
+    }
+
+    // Group changes whose context would overlap into one hunk
+    let mut hunks: Vec<(usize, usize)> = vec![];
+    for &change in &changes {
+        let start = change.saturating_sub(DIFF_CONTEXT);
+        let end = (change + DIFF_CONTEXT + 1).min(edits.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    // Line number (0-based) in old and new text before each edit
+    let mut positions = Vec::with_capacity(edits.len());
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for edit in &edits {
+        positions.push((old_pos, new_pos));
+        match edit {
+            Edit::Same(_) => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            Edit::Removed(_) => old_pos += 1,
+            Edit::Added(_) => new_pos += 1,
+        }
+    }
+
+    let mut lines = vec![format!("--- {}", old_label), format!("+++ {}", new_label)];
+    for (start, end) in hunks {
+        let hunk = &edits[start..end];
+        let old_count = hunk
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Added(_)))
+            .count();
+        let new_count = hunk
+            .iter()
+            .filter(|edit| !matches!(edit, Edit::Removed(_)))
+            .count();
+        let (old_start, new_start) = positions[start];
+        // An empty side is numbered by the line before it, as diff -u does
+        lines.push(format!(
+            "@@ -{},{} +{},{} @@",
+            old_start + usize::from(old_count > 0),
+            old_count,
+            new_start + usize::from(new_count > 0),
+            new_count
+        ));
+        for edit in hunk {
+            lines.push(match edit {
+                Edit::Same(line) => format!(" {}", line),
+                Edit::Removed(line) => format!("-{}", line),
+                Edit::Added(line) => format!("+{}", line),
+            });
+        }
+    }
+    lines
+}
+
+// Longest-common-subsequence edit script; configs are small enough for the O(n*m) table
+fn line_edits<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Edit<'a>> {
+    let (n, m) = (old.len(), new.len());
+    // common[i][j] = LCS length of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i] == new[j] {
+            edits.push(Edit::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            edits.push(Edit::Removed(old[i]));
+            i += 1;
+        } else {
+            edits.push(Edit::Added(new[j]));
+            j += 1;
+        }
+    }
+    edits.extend(old[i..].iter().map(|line| Edit::Removed(line)));
+    edits.extend(new[j..].iter().map(|line| Edit::Added(line)));
+    edits
+}
@@ -0,0 +1,118 @@
+use super::{Mainpage, status_span, yaml_page::yaml_to_lines};
+use crate::app::history::format_timestamp;
+use ratatui::{
+    Terminal,
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+pub fn render_history_page_ui<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    mp_struct: &mut Mainpage,
+) {
+    terminal
+        .draw(|f| {
+            let size = f.area();
+
+            let layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+                .split(size);
+
+            // --- Left side (past runs, newest first) ---
+            let records = mp_struct.get_history();
+            let run_items: Vec<ListItem> = records
+                .iter()
+                .map(|record| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(format!(
+                            " {}  {}",
+                            format_timestamp(record.get_started_at()),
+                            record.get_task_name()
+                        )),
+                        status_span(Some(record.get_status())),
+                    ]))
+                })
+                .collect();
+
+            let mut run_list_state = ListState::default();
+            if !records.is_empty() {
+                run_list_state.select(Some(mp_struct.get_history_selection()));
+            }
+
+            let run_list = List::new(run_items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Run History (Esc to go back)"),
+                )
+                .highlight_symbol(" > ")
+                .highlight_style(
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                );
+            f.render_stateful_widget(run_list, layout[0], &mut run_list_state);
+
+            // --- Right side (details of the selected run) ---
+            let mut detail_lines: Vec<Line> = vec![];
+            match records.get(mp_struct.get_history_selection()) {
+                Some(record) => {
+                    let exit_code = match record.get_exit_code() {
+                        Some(code) => code.to_string(),
+                        None => String::from("-"),
+                    };
+                    let run_dir = record.get_run_dir().clone().unwrap_or_default();
+                    let wandb_url = record.get_wandb_url().clone().unwrap_or_default();
+                    let fields = [
+                        ("Task", record.get_task_name().to_string()),
+                        ("Environment", record.get_environment().to_string()),
+                        ("Directory", record.get_directory().to_string()),
+                        ("Attempt", record.get_attempt().to_string()),
+                        ("Started", format_timestamp(record.get_started_at())),
+                        ("Ended", format_timestamp(record.get_ended_at())),
+                        ("Status", record.get_status().label()),
+                        ("Exit code", exit_code),
+                        ("Run dir", run_dir),
+                        ("W&B", wandb_url),
+                        ("Log", record.get_log_path().to_string()),
+                        ("Command", record.get_command().to_string()),
+                    ];
+                    for (name, value) in fields {
+                        detail_lines.push(Line::from(vec![
+                            Span::styled(
+                                format!("{:>12}: ", name),
+                                Style::default().fg(Color::Cyan),
+                            ),
+                            Span::raw(value),
+                        ]));
+                    }
+                    detail_lines.push(Line::from(""));
+                    detail_lines.push(Line::from(Span::styled(
+                        "Config:",
+                        Style::default().fg(Color::Cyan),
+                    )));
+                    match record.get_yaml() {
+                        Some(yaml) => {
+                            detail_lines.extend(yaml_to_lines(yaml).into_iter().map(Line::from))
+                        }
+                        None => detail_lines.push(Line::from("  (no YAML was written)")),
+                    }
+                }
+                None => detail_lines.push(Line::from("No runs recorded yet.")),
+            }
+
+            // Long configs scroll, keeping at least the last line in view
+            mp_struct.clamp_history_scroll(detail_lines.len().saturating_sub(1));
+            let scroll = u16::try_from(mp_struct.get_history_scroll()).unwrap_or(u16::MAX);
+            let details = Paragraph::new(detail_lines).scroll((scroll, 0)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("Run Details (PageUp/PageDown: scroll)"),
+            );
+            f.render_widget(details, layout[1]);
+        })
+        .unwrap();
+}
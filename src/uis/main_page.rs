@@ -1,13 +1,13 @@
-use super::Mainpage;
+use super::{Mainpage, highlight, status_span};
+use crate::app::CreationState;
+use crate::runner::progress::TrainingPoint;
 use ratatui::{
     Terminal,
-    backend::{self, CrosstermBackend},
     layout::Alignment,
-    layout::{Constraint, Direction, Layout, Rect},
-    prelude::Backend,
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Sparkline, Wrap},
 };
 
 pub fn render_main_page_ui<B: ratatui::backend::Backend>(
@@ -16,7 +16,7 @@ pub fn render_main_page_ui<B: ratatui::backend::Backend>(
 ) {
     terminal
         .draw(|f| {
-            let size = f.size();
+            let size = f.area();
 
             let layout = Layout::default()
                 .direction(Direction::Horizontal)
@@ -24,17 +24,27 @@ pub fn render_main_page_ui<B: ratatui::backend::Backend>(
                 .split(size);
 
             let task_names = mp_struct.get_task_queue_names();
-            let left_block = Block::default().borders(Borders::ALL).title("Task Queue");
+            let left_block = Block::default()
+                .borders(Borders::ALL)
+                .title("Task Queue (d: clear finished)");
 
             let selected_index = Some(mp_struct.task_selection + 1);
 
             let task_items: Vec<ListItem> = {
                 let mut items = vec![ListItem::new(" ")]; // blank line after title
-                items.extend(
-                    task_names
-                        .iter()
-                        .map(|task| ListItem::new(Span::raw(format!("   {}", task)))),
-                );
+                items.extend(task_names.iter().enumerate().map(|(i, task)| {
+                    let mut spans = vec![
+                        Span::raw(format!("   {}", task)),
+                        status_span(mp_struct.get_task_statuses().get(i)),
+                    ];
+                    if let Some(state) = mp_struct.get_task_remote_state(i) {
+                        spans.push(Span::styled(
+                            format!(" {}", state),
+                            Style::default().fg(Color::DarkGray),
+                        ));
+                    }
+                    ListItem::new(Line::from(spans))
+                }));
                 items
             };
 
@@ -65,11 +75,51 @@ pub fn render_main_page_ui<B: ratatui::backend::Backend>(
                 .split(layout[1]);
 
             let right_top_block = Block::default().borders(Borders::NONE);
-            let right_bottom_block = Block::default().borders(Borders::NONE);
+            let right_bottom_block = Block::default()
+                .borders(Borders::TOP)
+                .title(format!("Output: {}", mp_struct.get_log_task_name()));
 
             // Render the two blocks
             f.render_widget(right_top_block, right_chunk[0]);
-            f.render_widget(right_bottom_block, right_chunk[1]);
+
+            // Keep clear of the surrounding "Options" border
+            let output_area = right_chunk[1].inner(Margin {
+                horizontal: 1,
+                vertical: 1,
+            });
+            // No sparkline is wider than the whole output area, so older points aren't copied
+            let progress: Vec<TrainingPoint> = mp_struct
+                .get_task_log()
+                .as_ref()
+                .map(|log| {
+                    let log = log.lock().unwrap();
+                    log.get_progress().recent(output_area.width as usize)
+                })
+                .unwrap_or_default();
+            // Training curves go above the log once the run has reported an iteration
+            let log_area = if progress.is_empty() {
+                output_area
+            } else {
+                let output_chunk = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(6), Constraint::Min(1)].as_ref())
+                    .split(output_area);
+                render_progress(f, output_chunk[0], &progress);
+                output_chunk[1]
+            };
+            let log_height = log_area.height.saturating_sub(1) as usize;
+            let log_lines: Vec<Line> = match mp_struct.get_task_log() {
+                Some(log) => log
+                    .lock()
+                    .unwrap()
+                    .window(log_height, mp_struct.get_log_scroll())
+                    .into_iter()
+                    .map(|line| Line::from(line.to_string()))
+                    .collect(),
+                None => vec![Line::from("No task has been started yet.")],
+            };
+            let log_paragraph = Paragraph::new(log_lines).block(right_bottom_block);
+            f.render_widget(log_paragraph, log_area);
 
             let upper_right_chunk = Layout::default()
                 .direction(Direction::Horizontal)
@@ -90,6 +140,9 @@ pub fn render_main_page_ui<B: ratatui::backend::Backend>(
                     ("Task Not Running", Color::Red)
                 };
 
+                let (running_workers, max_workers) = mp_struct.get_workers();
+                let workers_text = format!("   Workers: {}/{}", running_workers, max_workers);
+
                 let location_text = format!(
                     "   Task Location: {}",
                     mp_struct.get_task_execution_location()
@@ -110,6 +163,14 @@ pub fn render_main_page_ui<B: ratatui::backend::Backend>(
                         Style::default().fg(Color::White),
                     )),
                     Line::from(Span::styled(wandb_text, Style::default().fg(wandb_color))),
+                    Line::from(Span::styled(
+                        workers_text,
+                        Style::default().fg(Color::White),
+                    )),
+                    Line::from(Span::styled(
+                        format!("   {}", mp_struct.get_storage_error()),
+                        Style::default().fg(Color::Red),
+                    )),
                 ]))
                 .alignment(Alignment::Left)
                 .block(Block::default().borders(Borders::NONE).title("Info"))
@@ -127,7 +188,7 @@ pub fn render_main_page_ui<B: ratatui::backend::Backend>(
                         Constraint::Length(3),
                         Constraint::Length(1),
                         Constraint::Length(3),
-                        Constraint::Percentage(40),
+                        Constraint::Length(4),
                     ]
                     .as_ref(),
                 )
@@ -149,12 +210,27 @@ pub fn render_main_page_ui<B: ratatui::backend::Backend>(
             );
             f.render_widget(environment_name_paragraph, upper_right_leck_chunk[3]);
 
+            let mut last_run_lines = vec![Line::from(mp_struct.get_task_run_dir())];
+            if !mp_struct.get_wandb_url().is_empty() {
+                last_run_lines.push(Line::from(Span::styled(
+                    format!("W&B: {}", mp_struct.get_wandb_url()),
+                    Style::default().fg(Color::Cyan),
+                )));
+            }
+            let run_dir_paragraph = Paragraph::new(last_run_lines).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_type(ratatui::widgets::BorderType::Rounded)
+                    .title("Last Run"),
+            );
+            f.render_widget(run_dir_paragraph, upper_right_leck_chunk[4]);
+
             // Right side (optional placeholder block)
             let right_block = Block::default().borders(Borders::ALL).title("Options");
             f.render_widget(right_block, layout[1]);
 
             if *mp_struct.get_create_window() {
-                let popup_area = centered_rect(50, 50, f.size()); // 50% width, 20% height of terminal
+                let popup_area = centered_rect(60, 90, f.area()); // 60% width, 90% height of terminal
 
                 let popup_block = Block::default()
                     .title("Create Task")
@@ -165,51 +241,231 @@ pub fn render_main_page_ui<B: ratatui::backend::Backend>(
                 // Render your popup content inside popup_area
                 f.render_widget(Clear, popup_area);
 
+                // (state, label, hint, text) of every editable field, top to bottom
+                let fields = [
+                    (
+                        CreationState::Taskname,
+                        "Task-Name",
+                        "",
+                        mp_struct.get_temp_name(),
+                    ),
+                    (
+                        CreationState::Envname,
+                        "Environment",
+                        "",
+                        mp_struct.get_temp_env(),
+                    ),
+                    (
+                        CreationState::Dir,
+                        "Directory",
+                        "",
+                        mp_struct.get_temp_dir(),
+                    ),
+                    (
+                        CreationState::Location,
+                        "Location",
+                        "local, ssh:<host> or slurm[:<sbatch options>]",
+                        mp_struct.get_temp_location(),
+                    ),
+                    (
+                        CreationState::Container,
+                        "Container",
+                        "docker:<image> or apptainer:<image>, then flags",
+                        mp_struct.get_temp_container(),
+                    ),
+                    (
+                        CreationState::Env,
+                        "Env Vars",
+                        "KEY=value, space separated, quote values with spaces",
+                        mp_struct.get_temp_env_vars(),
+                    ),
+                    (
+                        CreationState::PythonEnv,
+                        "Python Env",
+                        "conda:<name> or venv:<path>",
+                        mp_struct.get_temp_python_env(),
+                    ),
+                    (
+                        CreationState::ConfigMode,
+                        "Config Mode",
+                        "file or overrides",
+                        mp_struct.get_temp_config_mode(),
+                    ),
+                    (
+                        CreationState::Wandb,
+                        "W&B",
+                        "on, <project> or <entity>/<project>",
+                        mp_struct.get_temp_wandb(),
+                    ),
+                    (
+                        CreationState::Retry,
+                        "Retry",
+                        "attempts delay-secs codes",
+                        mp_struct.get_temp_retry(),
+                    ),
+                    (
+                        CreationState::Timeout,
+                        "Timeout",
+                        "e.g. 90m, 12h",
+                        mp_struct.get_temp_timeout(),
+                    ),
+                    (
+                        CreationState::Depends,
+                        "Depends On",
+                        "task names, comma separated",
+                        mp_struct.get_temp_depends(),
+                    ),
+                ];
+
+                // One row per field, so the popup fits a 24 row terminal
+                let mut constraints = vec![Constraint::Length(1)];
+                constraints.extend(fields.iter().map(|_| Constraint::Length(1)));
+                constraints.push(Constraint::Length(1)); // Hint of the field being edited
+                constraints.push(Constraint::Min(1)); // Error line
                 let popup_window = Layout::default()
                     .direction(Direction::Vertical)
-                    .constraints(
-                        [
-                            Constraint::Length(1),
-                            Constraint::Length(3),
-                            Constraint::Length(1),
-                            Constraint::Length(3),
-                            Constraint::Length(1),
-                            Constraint::Length(3),
-                        ]
-                        .as_ref(),
-                    )
+                    .horizontal_margin(2)
+                    .constraints(constraints)
                     .split(popup_area);
 
-                let task_name = Paragraph::new(mp_struct.get_temp_name()).block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_type(ratatui::widgets::BorderType::Rounded)
-                        .title("Task-Name"),
-                );
-                f.render_widget(task_name, popup_window[1]);
+                let label_width = fields.iter().map(|(_, label, ..)| label.len()).max();
+                let label_width = label_width.unwrap_or(0);
+                let active = mp_struct.get_creation_state();
+                let mut hint = "";
+                for (i, (state, label, field_hint, text)) in fields.into_iter().enumerate() {
+                    let style = if state == active {
+                        hint = field_hint;
+                        highlight(true)
+                    } else {
+                        Style::default()
+                    };
+                    let row = Line::from(vec![
+                        Span::styled(format!("{:>width$}: ", label, width = label_width), style),
+                        Span::raw(text),
+                    ]);
+                    f.render_widget(Paragraph::new(row), popup_window[i + 1]);
+                }
+
+                let hint = Paragraph::new(Span::styled(hint, Style::default().fg(Color::DarkGray)));
+                f.render_widget(hint, popup_window[popup_window.len() - 2]);
+
+                let create_error = Paragraph::new(Span::styled(
+                    mp_struct.get_create_error(),
+                    Style::default().fg(Color::Red),
+                ))
+                .wrap(Wrap { trim: true });
+                f.render_widget(create_error, popup_window[popup_window.len() - 1]);
+
+                f.render_widget(popup_block, popup_area);
+            }
 
-                let env_name = Paragraph::new(mp_struct.get_temp_env()).block(
+            if let Some(diff_lines) = mp_struct.get_diff_preview() {
+                let diff_area = centered_rect(80, 80, f.area());
+                let lines: Vec<Line> = diff_lines
+                    .iter()
+                    .skip(mp_struct.get_diff_scroll())
+                    .map(|line| {
+                        let color = if line.starts_with("+++") || line.starts_with("---") {
+                            Color::White
+                        } else if line.starts_with('+') {
+                            Color::Green
+                        } else if line.starts_with('-') {
+                            Color::Red
+                        } else if line.starts_with("@@") {
+                            Color::Cyan
+                        } else {
+                            Color::Gray
+                        };
+                        Line::from(Span::styled(line.as_str(), Style::default().fg(color)))
+                    })
+                    .collect();
+                let diff = Paragraph::new(lines).block(
                     Block::default()
+                        .title("Config Changes (Enter: queue, Esc: back, Up/Down: scroll)")
                         .borders(Borders::ALL)
                         .border_type(ratatui::widgets::BorderType::Rounded)
-                        .title("Environment-Name"),
+                        .style(Style::default().fg(Color::White).bg(Color::Black)),
                 );
-                f.render_widget(env_name, popup_window[3]);
+                f.render_widget(Clear, diff_area);
+                f.render_widget(diff, diff_area);
+            }
 
-                let dir_name = Paragraph::new(mp_struct.get_temp_dir()).block(
+            if *mp_struct.get_quitting() {
+                let (running, _) = mp_struct.get_workers();
+                let quit_area = centered_rect(50, 20, f.area());
+                let quit = Paragraph::new(vec![
+                    Line::from(format!(
+                        "Waiting for {} running task(s) to end before quitting.",
+                        running
+                    )),
+                    Line::from(Span::styled(
+                        "x: cancel them",
+                        Style::default().fg(Color::Yellow),
+                    )),
+                ])
+                .wrap(Wrap { trim: false })
+                .block(
                     Block::default()
+                        .title("Quitting")
                         .borders(Borders::ALL)
                         .border_type(ratatui::widgets::BorderType::Rounded)
-                        .title("Directory"),
+                        .style(Style::default().fg(Color::White).bg(Color::Black)),
                 );
-                f.render_widget(dir_name, popup_window[5]);
-
-                f.render_widget(popup_block, popup_area);
+                f.render_widget(Clear, quit_area);
+                f.render_widget(quit, quit_area);
             }
         })
         .unwrap();
 }
 
+type Metric = fn(&TrainingPoint) -> Option<f64>;
+
+// One sparkline per RSL-RL metric, titled with its latest value
+fn render_progress(f: &mut ratatui::Frame, area: Rect, progress: &[TrainingPoint]) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3); 3].as_ref())
+        .split(area);
+    let metrics: [(&str, Metric, Color); 3] = [
+        ("Reward", |point| point.mean_reward, Color::Green),
+        ("Ep Length", |point| point.episode_length, Color::Cyan),
+        ("Steps/s", |point| point.fps, Color::Magenta),
+    ];
+    let iteration = progress.last().map_or(0, |point| point.iteration);
+
+    for ((name, value_of, color), chunk) in metrics.into_iter().zip(chunks.iter()) {
+        let block = Block::default().borders(Borders::ALL);
+        // The sparkline draws from the left, so only pass the newest points that fit
+        let width = block.inner(*chunk).width as usize;
+        let values: Vec<Option<f64>> = progress[progress.len().saturating_sub(width)..]
+            .iter()
+            .map(value_of)
+            .collect();
+        let latest = progress.iter().rev().find_map(value_of);
+        let title = match latest {
+            Some(latest) => format!("{} {:.2} @{}", name, latest, iteration),
+            None => format!("{} -", name),
+        };
+        let sparkline = Sparkline::default()
+            .block(block.title(title))
+            .data(scale_to_bars(&values))
+            .style(Style::default().fg(color));
+        f.render_widget(sparkline, *chunk);
+    }
+}
+
+// Shifts the values so the lowest one sits at the bottom; sparklines only take u64s
+fn scale_to_bars(values: &[Option<f64>]) -> Vec<Option<u64>> {
+    let known = values.iter().flatten();
+    let min = known.clone().fold(f64::INFINITY, |a, &b| a.min(b));
+    let max = known.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    let range = if max > min { max - min } else { 1.0 };
+    values
+        .iter()
+        .map(|value| value.map(|value| (1.0 + (value - min) / range * 99.0) as u64))
+        .collect()
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -1,68 +1,424 @@
+pub mod checkpoints_page;
+pub mod diff;
+pub mod history_page;
 pub mod main_page;
+pub mod scalars_page;
+use checkpoints_page::render_checkpoints_page_ui;
+use history_page::render_history_page_ui;
 use main_page::render_main_page_ui;
+use scalars_page::render_scalars_page_ui;
 pub mod yaml_page;
+use crate::app::CreationState;
+use crate::app::history::RunRecord;
+use crate::app::task_config::TaskStatus;
+use crate::runner::checkpoints::CheckpointRun;
+use crate::runner::log_buffer::SharedLog;
+use crate::runner::tensorboard::{EventRun, ScalarSeries};
 use ratatui::{
     Terminal,
-    backend::{self, CrosstermBackend},
-    layout::Alignment,
-    layout::{Constraint, Direction, Layout, Rect},
-    prelude::Backend,
     style::{Color, Modifier, Style},
-    text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    text::Span,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use serde_yaml::from_str;
 use serde_yaml::{Number, Value};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::str::FromStr;
-use std::{
-    fs::File,
-    io::{self, Read, Write},
-    panic,
-    path::Path,
-    ptr::null,
-    time::{Duration, Instant},
-};
+use std::{fs::File, io::Read, path::Path};
 use yaml_page::render_yaml_page_ui;
 
 pub struct Mainpage {
     task_selection: usize,
     active_view: bool,
     task_list: Vec<String>,
+    task_statuses: Vec<TaskStatus>,
+    task_remote_states: Vec<Option<String>>, // e.g. "PENDING #1234" for a Slurm job
     task_running: bool,
+    running_workers: usize,
+    max_workers: usize,
+    quitting: bool, // Waiting for running tasks to end before the app exits
     task_execution_location: String,
+    task_run_dir: String, // Snapshot directory of the selected task's latest run
+    storage_error: String,
     wandb: bool,
+    wandb_url: String, // W&B run page of the selected task, empty until known
     create_window: bool,
+    creation_state: CreationState, // Field being edited in the create popup
     yaml_mode: bool,
     create_task_name: String,
     create_env: String,
     create_dir: String,
+    create_location: String,
+    create_container: String,
+    create_env_vars: String,
+    create_python_env: String,
+    create_config_mode: String,
+    create_wandb: String,
+    create_retry: String,
+    create_timeout: String,
+    create_depends: String,
+    create_error: String, // Why the task in the create popup can't be queued yet
     temp_yaml: Option<Value>,
     yaml_selection: usize,
     update_yaml_selection: bool,
     yaml_update_text: String,
+    log_task_id: Option<usize>,
+    log_task_name: String,
+    task_log: Option<SharedLog>,
+    log_scroll: usize, // Lines scrolled up from the newest output, 0 follows the tail
+    history_mode: bool,
+    history: Vec<RunRecord>, // Newest run first
+    history_selection: usize,
+    history_scroll: usize, // Lines of the run details scrolled past, from the top
+    diff_preview: Option<Vec<String>>, // Config changes awaiting confirmation before queueing
+    diff_scroll: usize,
+    diff_pending: bool, // Still checking the task, so the preview can't be confirmed yet
+    scalars_mode: bool,
+    scalar_runs: Vec<EventRun>, // TensorBoard runs under the selected task's logs
+    scalar_run_selection: usize,
+    scalars: BTreeMap<String, ScalarSeries>, // Tags of the loaded run
+    scalar_tag_selection: usize,
+    scalar_tags_focused: bool, // Up/Down move through the tags rather than the runs
+    scalars_message: String,   // Why there is nothing to plot, if so
+    checkpoints_mode: bool,
+    checkpoint_runs: Vec<CheckpointRun>, // Runs with checkpoints under the selected task's logs
+    checkpoint_run_selection: usize,
+    checkpoint_selection: usize,
+    checkpoint_files_focused: bool, // Up/Down move through the checkpoints rather than the runs
+    checkpoints_message: String,    // Last thing queued, or why nothing was found
+}
+impl Default for Mainpage {
+    fn default() -> Self {
+        Self::new()
+    }
 }
+
 impl Mainpage {
     pub fn new() -> Self {
         Self {
             task_selection: 0,
             active_view: false,
             task_list: vec![],
+            task_statuses: vec![],
+            task_remote_states: vec![],
             task_running: false,
+            running_workers: 0,
+            max_workers: 1,
+            quitting: false,
             task_execution_location: String::from("LOCAL"),
+            task_run_dir: String::new(),
+            storage_error: String::from(""),
             yaml_mode: false,
             wandb: false,
+            wandb_url: String::new(),
             create_window: false,
+            creation_state: CreationState::Null,
             create_task_name: String::from(""),
             create_env: String::from(""),
             create_dir: String::from(""),
+            create_location: String::from(""),
+            create_container: String::from(""),
+            create_env_vars: String::from(""),
+            create_python_env: String::from(""),
+            create_config_mode: String::from(""),
+            create_wandb: String::from(""),
+            create_retry: String::from(""),
+            create_timeout: String::from(""),
+            create_depends: String::from(""),
+            create_error: String::from(""),
             temp_yaml: None,
             yaml_selection: 0,
             update_yaml_selection: false,
             yaml_update_text: String::from(""),
+            log_task_id: None,
+            log_task_name: String::from(""),
+            task_log: None,
+            log_scroll: 0,
+            history_mode: false,
+            history: vec![],
+            history_selection: 0,
+            history_scroll: 0,
+            diff_preview: None,
+            diff_scroll: 0,
+            diff_pending: false,
+            scalars_mode: false,
+            scalar_runs: vec![],
+            scalar_run_selection: 0,
+            scalars: BTreeMap::new(),
+            scalar_tag_selection: 0,
+            scalar_tags_focused: false,
+            scalars_message: String::new(),
+            checkpoints_mode: false,
+            checkpoint_runs: vec![],
+            checkpoint_run_selection: 0,
+            checkpoint_selection: 0,
+            checkpoint_files_focused: false,
+            checkpoints_message: String::new(),
+        }
+    }
+    // ------------ Diff preview ----------------
+    pub fn open_diff_preview(&mut self, lines: Vec<String>) {
+        self.diff_preview = Some(lines);
+        self.diff_scroll = 0;
+        self.diff_pending = false;
+    }
+    pub fn get_diff_pending(&self) -> bool {
+        self.diff_pending
+    }
+    pub fn set_diff_pending(&mut self, pending: bool) {
+        self.diff_pending = pending;
+    }
+    /// Swaps in the finished diff, keeping the scroll position if it still fits.
+    pub fn update_diff_preview(&mut self, lines: Vec<String>) {
+        self.diff_scroll = self.diff_scroll.min(lines.len().saturating_sub(1));
+        self.diff_preview = Some(lines);
+        self.diff_pending = false;
+    }
+    pub fn close_diff_preview(&mut self) {
+        self.diff_preview = None;
+    }
+    pub fn get_diff_preview(&self) -> Option<&[String]> {
+        self.diff_preview.as_deref()
+    }
+    pub fn get_diff_scroll(&self) -> usize {
+        self.diff_scroll
+    }
+    pub fn scroll_diff_down(&mut self, lines: usize) {
+        let len = self.diff_preview.as_ref().map_or(0, |lines| lines.len());
+        self.diff_scroll = (self.diff_scroll + lines).min(len.saturating_sub(1));
+    }
+    pub fn scroll_diff_up(&mut self, lines: usize) {
+        self.diff_scroll = self.diff_scroll.saturating_sub(lines);
+    }
+
+    // ------------ Checkpoints ----------------
+    pub fn open_checkpoints(&mut self, runs: Vec<CheckpointRun>) {
+        self.checkpoint_runs = runs;
+        self.checkpoint_run_selection = 0;
+        self.checkpoint_selection = 0;
+        self.checkpoint_files_focused = false;
+        self.checkpoints_message = String::new();
+        self.checkpoints_mode = true;
+    }
+    pub fn close_checkpoints(&mut self) {
+        self.checkpoints_mode = false;
+    }
+    pub fn get_checkpoints_mode(&self) -> &bool {
+        &self.checkpoints_mode
+    }
+    pub fn get_checkpoint_runs(&self) -> &[CheckpointRun] {
+        &self.checkpoint_runs
+    }
+    pub fn get_checkpoint_run_selection(&self) -> usize {
+        self.checkpoint_run_selection
+    }
+    pub fn get_selected_checkpoint_run(&self) -> Option<&CheckpointRun> {
+        self.checkpoint_runs.get(self.checkpoint_run_selection)
+    }
+    pub fn get_checkpoint_selection(&self) -> usize {
+        self.checkpoint_selection
+    }
+    /// The selected run and checkpoint file name.
+    pub fn get_selected_checkpoint(&self) -> Option<(&CheckpointRun, &str)> {
+        let run = self.get_selected_checkpoint_run()?;
+        let checkpoint = run.get_checkpoints().get(self.checkpoint_selection)?;
+        Some((run, checkpoint))
+    }
+    pub fn get_checkpoint_files_focused(&self) -> &bool {
+        &self.checkpoint_files_focused
+    }
+    pub fn set_checkpoint_files_focused(&mut self, focused: bool) {
+        self.checkpoint_files_focused = focused;
+    }
+    pub fn get_checkpoints_message(&self) -> &str {
+        &self.checkpoints_message
+    }
+    pub fn set_checkpoints_message<S: Into<String>>(&mut self, message: S) {
+        self.checkpoints_message = message.into();
+    }
+    pub fn increase_checkpoint_selection(&mut self) {
+        if self.checkpoint_files_focused {
+            let len = self
+                .get_selected_checkpoint_run()
+                .map_or(0, |run| run.get_checkpoints().len());
+            if len > 0 {
+                self.checkpoint_selection = (self.checkpoint_selection + 1) % len;
+            }
+        } else if !self.checkpoint_runs.is_empty() {
+            let len = self.checkpoint_runs.len();
+            self.checkpoint_run_selection = (self.checkpoint_run_selection + 1) % len;
+            self.checkpoint_selection = 0;
         }
     }
+    pub fn decrease_checkpoint_selection(&mut self) {
+        if self.checkpoint_files_focused {
+            let len = self
+                .get_selected_checkpoint_run()
+                .map_or(0, |run| run.get_checkpoints().len());
+            if len > 0 {
+                self.checkpoint_selection = (self.checkpoint_selection + len - 1) % len;
+            }
+        } else if !self.checkpoint_runs.is_empty() {
+            let len = self.checkpoint_runs.len();
+            self.checkpoint_run_selection = (self.checkpoint_run_selection + len - 1) % len;
+            self.checkpoint_selection = 0;
+        }
+    }
+
+    // ------------ TensorBoard scalars ----------------
+    pub fn open_scalars(&mut self, runs: Vec<EventRun>) {
+        self.scalar_runs = runs;
+        self.scalar_run_selection = 0;
+        self.scalars = BTreeMap::new();
+        self.scalar_tag_selection = 0;
+        self.scalar_tags_focused = false;
+        self.scalars_message = String::new();
+        self.scalars_mode = true;
+    }
+    pub fn close_scalars(&mut self) {
+        self.scalars_mode = false;
+    }
+    pub fn get_scalars_mode(&self) -> &bool {
+        &self.scalars_mode
+    }
+    pub fn get_scalar_runs(&self) -> &[EventRun] {
+        &self.scalar_runs
+    }
+    pub fn get_scalar_run_selection(&self) -> usize {
+        self.scalar_run_selection
+    }
+    pub fn get_selected_scalar_run(&self) -> Option<&EventRun> {
+        self.scalar_runs.get(self.scalar_run_selection)
+    }
+    pub fn set_scalars(&mut self, scalars: BTreeMap<String, ScalarSeries>) {
+        self.scalars = scalars;
+        self.scalar_tag_selection = 0;
+    }
+    pub fn get_scalars(&self) -> &BTreeMap<String, ScalarSeries> {
+        &self.scalars
+    }
+    pub fn get_scalar_tag_selection(&self) -> usize {
+        self.scalar_tag_selection
+    }
+    pub fn get_selected_scalar(&self) -> Option<(&String, &ScalarSeries)> {
+        self.scalars.iter().nth(self.scalar_tag_selection)
+    }
+    pub fn get_scalar_tags_focused(&self) -> &bool {
+        &self.scalar_tags_focused
+    }
+    pub fn set_scalar_tags_focused(&mut self, focused: bool) {
+        self.scalar_tags_focused = focused;
+    }
+    pub fn get_scalars_message(&self) -> &str {
+        &self.scalars_message
+    }
+    pub fn set_scalars_message<S: Into<String>>(&mut self, message: S) {
+        self.scalars_message = message.into();
+    }
+    pub fn increase_scalar_selection(&mut self) {
+        let (selection, len) = if self.scalar_tags_focused {
+            (&mut self.scalar_tag_selection, self.scalars.len())
+        } else {
+            (&mut self.scalar_run_selection, self.scalar_runs.len())
+        };
+        if len > 0 {
+            *selection = (*selection + 1) % len;
+        }
+    }
+    pub fn decrease_scalar_selection(&mut self) {
+        let (selection, len) = if self.scalar_tags_focused {
+            (&mut self.scalar_tag_selection, self.scalars.len())
+        } else {
+            (&mut self.scalar_run_selection, self.scalar_runs.len())
+        };
+        if len > 0 {
+            *selection = (*selection + len - 1) % len;
+        }
+    }
+
+    // ------------ Run history ----------------
+    pub fn open_history(&mut self, mut records: Vec<RunRecord>) {
+        records.reverse();
+        self.history = records;
+        self.history_selection = 0;
+        self.history_scroll = 0;
+        self.history_mode = true;
+    }
+    pub fn close_history(&mut self) {
+        self.history_mode = false;
+    }
+    pub fn get_history_mode(&self) -> &bool {
+        &self.history_mode
+    }
+    pub fn get_history(&self) -> &[RunRecord] {
+        &self.history
+    }
+    pub fn get_history_selection(&self) -> usize {
+        self.history_selection
+    }
+    pub fn get_history_scroll(&self) -> usize {
+        self.history_scroll
+    }
+    // Clamped to the details when they are drawn, which is where their length is known
+    pub fn scroll_history_down(&mut self, lines: usize) {
+        self.history_scroll = self.history_scroll.saturating_add(lines);
+    }
+    pub fn scroll_history_up(&mut self, lines: usize) {
+        self.history_scroll = self.history_scroll.saturating_sub(lines);
+    }
+    pub fn clamp_history_scroll(&mut self, max: usize) {
+        self.history_scroll = self.history_scroll.min(max);
+    }
+    pub fn increase_history_selection(&mut self) {
+        let len = self.history.len();
+        if len > 0 {
+            self.history_selection = (self.history_selection + 1) % len;
+            self.history_scroll = 0;
+        }
+    }
+    pub fn decrease_history_selection(&mut self) {
+        let len = self.history.len();
+        if len > 0 {
+            self.history_selection = (self.history_selection + len - 1) % len;
+            self.history_scroll = 0;
+        }
+    }
+    // -------------------------------------------
+
+    // ------------ Task output ----------------
+    pub fn set_task_log(&mut self, task_id: usize, task_name: &str, log: Option<SharedLog>) {
+        if self.log_task_id != Some(task_id) {
+            // New task on screen, jump back to the newest output
+            self.log_scroll = 0;
+            self.log_task_id = Some(task_id);
+        }
+        self.log_task_name = String::from(task_name);
+        self.task_log = log;
+    }
+    pub fn get_log_task_name(&self) -> &str {
+        &self.log_task_name
+    }
+    pub fn get_task_log(&self) -> &Option<SharedLog> {
+        &self.task_log
+    }
+    pub fn get_log_scroll(&self) -> usize {
+        self.log_scroll
+    }
+    pub fn scroll_log_up(&mut self, lines: usize) {
+        let total = match &self.task_log {
+            Some(log) => log.lock().unwrap().len(),
+            None => 0,
+        };
+        self.log_scroll = (self.log_scroll + lines).min(total.saturating_sub(1));
+    }
+    pub fn scroll_log_down(&mut self, lines: usize) {
+        self.log_scroll = self.log_scroll.saturating_sub(lines);
+    }
+    // -------------------------------------------
+
+    pub fn get_yaml(&self) -> Option<&Value> {
+        self.temp_yaml.as_ref()
+    }
     pub fn take_yaml(&mut self) -> Option<Value> {
         self.temp_yaml.take()
     }
@@ -124,28 +480,20 @@ impl Mainpage {
     //     }
     // }
     fn get_yaml_line_count(&self) -> usize {
-        if let Some(yaml_value) = &self.temp_yaml {
-            match yaml_value {
-                Value::Mapping(root_map) => {
-                    // If the root is a map, we iterate through its *values*.
-                    // We DO NOT count the `root_map.len()` here.
-                    // Instead, we sum the counts from what each value contains.
-                    let mut total_nested_count = 0;
-                    for (_key, nested_value) in root_map {
-                        // Call the helper to count all key-value pairs found within this nested value.
-                        total_nested_count += count_all_found_key_value_pairs(nested_value);
-                    }
-                    total_nested_count
-                }
-                _ => {
-                    // If the root is not a map (e.g., a sequence or a scalar),
-                    // there are no "nested" key-value pairs of the type you're asking for.
-                    // For example, if the YAML was just "foo: bar", "foo" would be top-level.
-                    0
-                }
+        if let Some(Value::Mapping(root_map)) = &self.temp_yaml {
+            // If the root is a map, we iterate through its *values*.
+            // We DO NOT count the `root_map.len()` here.
+            // Instead, we sum the counts from what each value contains.
+            let mut total_nested_count = 0;
+            for (_key, nested_value) in root_map {
+                // Call the helper to count all key-value pairs found within this nested value.
+                total_nested_count += count_all_found_key_value_pairs(nested_value);
             }
+            total_nested_count
         } else {
-            0 // No YAML data means 0 key-value pairs
+            // No YAML data, or the root is not a map (e.g., a sequence or a scalar),
+            // so there are no "nested" key-value pairs of the type you're asking for.
+            0
         }
     }
     pub fn write_to_yaml_buffer(&mut self, c: char) {
@@ -207,8 +555,16 @@ impl Mainpage {
         self.create_env = String::from(env_name);
         self.create_dir = String::from(dir);
     }
-    pub fn get_current_task_selection_name(&self) -> &str {
-        &self.task_list[self.task_selection]
+    pub fn get_selected_task_name(&self) -> Option<&str> {
+        self.task_list
+            .get(self.task_selection)
+            .map(|name| name.as_str())
+    }
+    pub fn get_creation_state(&self) -> CreationState {
+        self.creation_state
+    }
+    pub fn set_creation_state(&mut self, creation_state: CreationState) {
+        self.creation_state = creation_state;
     }
     pub fn get_yaml_mode(&self) -> &bool {
         &self.yaml_mode
@@ -237,9 +593,111 @@ impl Mainpage {
     pub fn set_temp_dir<S: Into<String>>(&mut self, dir: S) {
         self.create_dir = dir.into();
     }
+    pub fn get_temp_location(&self) -> &str {
+        &self.create_location
+    }
+    pub fn set_temp_location<S: Into<String>>(&mut self, location: S) {
+        self.create_location = location.into();
+    }
+    pub fn get_temp_container(&self) -> &str {
+        &self.create_container
+    }
+    pub fn set_temp_container<S: Into<String>>(&mut self, container: S) {
+        self.create_container = container.into();
+    }
+    pub fn get_temp_env_vars(&self) -> &str {
+        &self.create_env_vars
+    }
+    pub fn set_temp_env_vars<S: Into<String>>(&mut self, env_vars: S) {
+        self.create_env_vars = env_vars.into();
+    }
+    pub fn get_temp_python_env(&self) -> &str {
+        &self.create_python_env
+    }
+    pub fn set_temp_python_env<S: Into<String>>(&mut self, python_env: S) {
+        self.create_python_env = python_env.into();
+    }
+    pub fn get_temp_config_mode(&self) -> &str {
+        &self.create_config_mode
+    }
+    pub fn set_temp_config_mode<S: Into<String>>(&mut self, config_mode: S) {
+        self.create_config_mode = config_mode.into();
+    }
+    pub fn get_temp_wandb(&self) -> &str {
+        &self.create_wandb
+    }
+    pub fn set_temp_wandb<S: Into<String>>(&mut self, wandb: S) {
+        self.create_wandb = wandb.into();
+    }
+    pub fn set_task_execution_location<S: Into<String>>(&mut self, location: S) {
+        self.task_execution_location = location.into();
+    }
+    pub fn get_temp_retry(&self) -> &str {
+        &self.create_retry
+    }
+    pub fn set_temp_retry<S: Into<String>>(&mut self, retry: S) {
+        self.create_retry = retry.into();
+    }
+    pub fn get_temp_timeout(&self) -> &str {
+        &self.create_timeout
+    }
+    pub fn set_temp_timeout<S: Into<String>>(&mut self, timeout: S) {
+        self.create_timeout = timeout.into();
+    }
+    pub fn get_temp_depends(&self) -> &str {
+        &self.create_depends
+    }
+    pub fn set_temp_depends<S: Into<String>>(&mut self, depends: S) {
+        self.create_depends = depends.into();
+    }
+    pub fn get_create_error(&self) -> &str {
+        &self.create_error
+    }
+    pub fn set_create_error<S: Into<String>>(&mut self, error: S) {
+        self.create_error = error.into();
+    }
     pub fn get_task_running(&self) -> &bool {
         &self.task_running
     }
+    pub fn set_task_running(&mut self, task_running: bool) {
+        self.task_running = task_running;
+    }
+    pub fn get_selected_task_index(&self) -> usize {
+        self.task_selection
+    }
+    pub fn get_storage_error(&self) -> &str {
+        &self.storage_error
+    }
+    pub fn set_storage_error<S: Into<String>>(&mut self, error: S) {
+        self.storage_error = error.into();
+    }
+    pub fn get_workers(&self) -> (usize, usize) {
+        (self.running_workers, self.max_workers)
+    }
+    pub fn set_workers(&mut self, running_workers: usize, max_workers: usize) {
+        self.running_workers = running_workers;
+        self.max_workers = max_workers;
+    }
+    pub fn get_quitting(&self) -> &bool {
+        &self.quitting
+    }
+    pub fn set_quitting(&mut self, quitting: bool) {
+        self.quitting = quitting;
+    }
+    pub fn get_task_statuses(&self) -> &[TaskStatus] {
+        &self.task_statuses
+    }
+    pub fn update_task_statuses(&mut self, task_statuses: Vec<TaskStatus>) {
+        self.task_statuses = task_statuses;
+    }
+    pub fn get_task_remote_state(&self, index: usize) -> Option<&str> {
+        self.task_remote_states
+            .get(index)
+            .and_then(|state| state.as_deref())
+    }
+    pub fn update_task_remote_states(&mut self, task_remote_states: Vec<Option<String>>) {
+        self.task_remote_states = task_remote_states;
+    }
     pub fn update_task_list(&mut self, task_list: Vec<&str>) {
         self.task_list.clear();
         for task_name in task_list {
@@ -252,6 +710,21 @@ impl Mainpage {
     pub fn get_wandb(&self) -> &bool {
         &self.wandb
     }
+    pub fn set_wandb(&mut self, wandb: bool) {
+        self.wandb = wandb;
+    }
+    pub fn get_wandb_url(&self) -> &str {
+        &self.wandb_url
+    }
+    pub fn set_wandb_url<S: Into<String>>(&mut self, wandb_url: S) {
+        self.wandb_url = wandb_url.into();
+    }
+    pub fn get_task_run_dir(&self) -> &str {
+        &self.task_run_dir
+    }
+    pub fn set_task_run_dir<S: Into<String>>(&mut self, run_dir: S) {
+        self.task_run_dir = run_dir.into();
+    }
     pub fn get_task_execution_location(&self) -> &str {
         &self.task_execution_location
     }
@@ -284,6 +757,12 @@ impl Mainpage {
             }
         }
     }
+    // Keeps the selection on the list after tasks were removed from it
+    pub fn clamp_selection(&mut self) {
+        self.task_selection = self
+            .task_selection
+            .min(self.task_list.len().saturating_sub(1));
+    }
 }
 
 pub fn render_page<B: ratatui::backend::Backend>(
@@ -292,18 +771,21 @@ pub fn render_page<B: ratatui::backend::Backend>(
 ) {
     if *mp_struct.get_yaml_mode() {
         render_yaml_page_ui(terminal, mp_struct);
+    } else if *mp_struct.get_history_mode() {
+        render_history_page_ui(terminal, mp_struct);
+    } else if *mp_struct.get_scalars_mode() {
+        render_scalars_page_ui(terminal, mp_struct);
+    } else if *mp_struct.get_checkpoints_mode() {
+        render_checkpoints_page_ui(terminal, mp_struct);
     } else {
         render_main_page_ui(terminal, mp_struct);
     }
 
-    match mp_struct.temp_yaml {
-        None => {
-            match read_yaml::<Value>("/Users/bmcc/Desktop/Test/config.yaml") {
-                Ok(value) => mp_struct.set_yaml_file(value),
-                Err(e) => (),
-            };
-        }
-        _ => (),
+    if mp_struct.temp_yaml.is_none() {
+        match read_yaml::<Value>("/Users/bmcc/Desktop/Test/config.yaml") {
+            Ok(value) => mp_struct.set_yaml_file(value),
+            Err(_e) => (),
+        };
     }
     // Example 1: Deserialize into a Config struct
     // match read_yaml::<Config>("/Users/bmcc/Desktop/Test/config.yaml") {
@@ -343,6 +825,31 @@ pub fn render_page<B: ratatui::backend::Backend>(
     // }
 }
 
+// Coloured "[Status]" tag shown after each task in the queue
+pub fn status_span(status: Option<&TaskStatus>) -> Span<'static> {
+    let color = match status {
+        Some(TaskStatus::Queued) | None => Color::Gray,
+        Some(TaskStatus::Running) => Color::Green,
+        Some(TaskStatus::Succeeded) => Color::Cyan,
+        Some(TaskStatus::Failed(_)) => Color::Red,
+        Some(TaskStatus::Cancelled) => Color::Magenta,
+        Some(TaskStatus::TimedOut) => Color::LightRed,
+        Some(TaskStatus::Skipped) => Color::DarkGray,
+    };
+    let label = status.map(|status| status.label()).unwrap_or_default();
+    Span::styled(format!("  [{}]", label), Style::default().fg(color))
+}
+
+// Selected row of a list, dimmed while another list on the page has the focus
+pub fn highlight(focused: bool) -> Style {
+    let color = if focused {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
 pub fn update_yaml_elements<F>(value: &mut Value, updater: &mut F)
 where
     F: FnMut(&mut Value),
@@ -390,7 +897,7 @@ where
 
     Ok(result)
 }
-fn flatten_value(value: &Value) -> Vec<(String, Value)> {
+pub fn flatten_value(value: &Value) -> Vec<(String, Value)> {
     let mut flat_vec = Vec::new(); // Changed to Vec
     flatten_recursive(value, String::new(), &mut flat_vec);
     flat_vec
@@ -424,6 +931,61 @@ fn flatten_recursive(value: &Value, prefix: String, flat_vec: &mut Vec<(String,
     }
 }
 
+/// Hydra command-line overrides that turn `base` into `edited`, key by
+/// flattened key: `key=value` for changes, `+key=value` for additions and
+/// `~key` for deletions.
+pub fn yaml_overrides(base: &Value, edited: &Value) -> Vec<String> {
+    let base_pairs = flatten_value(base);
+    let edited_pairs = flatten_value(edited);
+    let mut overrides = vec![];
+    for (key, value) in &edited_pairs {
+        match base_pairs.iter().find(|(base_key, _)| base_key == key) {
+            Some((_, base_value)) if base_value == value => {}
+            Some(_) => overrides.push(format!("{}={}", key, hydra_value(value))),
+            None => overrides.push(format!("+{}={}", key, hydra_value(value))),
+        }
+    }
+    for (key, _) in &base_pairs {
+        if !edited_pairs.iter().any(|(edited_key, _)| edited_key == key) {
+            overrides.push(format!("~{}", key));
+        }
+    }
+    overrides
+}
+
+// A value in Hydra's override grammar; strings get quoted unless they are plain words
+fn hydra_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::from("null"),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => {
+            let is_plain = !s.is_empty()
+                && s.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "_-./".contains(c))
+                && s.parse::<f64>().is_err()
+                && !["true", "false", "null"].contains(&s.to_ascii_lowercase().as_str());
+            if is_plain {
+                s.clone()
+            } else {
+                format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+        }
+        Value::Sequence(items) => {
+            let items: Vec<String> = items.iter().map(hydra_value).collect();
+            format!("[{}]", items.join(","))
+        }
+        Value::Mapping(map) => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|(k, v)| format!("{}:{}", hydra_value(k), hydra_value(v)))
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+        Value::Tagged(tagged) => hydra_value(&tagged.value),
+    }
+}
+
 /// Flattens a `serde_yaml::Value` and returns the key at the specified index
 /// from the original-order flattened key-value pairs.
 ///
@@ -450,7 +1012,7 @@ fn set_nested_value_mut(
     // --- New logic to parse new_string_value ---
     let parsed_value = if let Ok(int_val) = new_string_value.parse::<i64>() {
         Value::Number(Number::from(int_val))
-    } else if let Ok(float_val) = new_string_value.parse::<f64>() {
+    } else if let Ok(_float_val) = new_string_value.parse::<f64>() {
         Value::Number(Number::from_str(&new_string_value).unwrap()) // Or handle error if FromStr fails (unlikely here)
     } else if new_string_value.eq_ignore_ascii_case("true") {
         Value::Bool(true)
@@ -0,0 +1,139 @@
+use super::{Mainpage, highlight};
+use ratatui::{
+    Terminal,
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Color, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        Axis, Block, Borders, Chart, Dataset, GraphType, List, ListItem, ListState, Paragraph,
+    },
+};
+
+pub fn render_scalars_page_ui<B: ratatui::backend::Backend>(
+    terminal: &mut Terminal<B>,
+    mp_struct: &Mainpage,
+) {
+    terminal
+        .draw(|f| {
+            let size = f.area();
+
+            let layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(30), Constraint::Percentage(70)].as_ref())
+                .split(size);
+            let left_chunk = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+                .split(layout[0]);
+
+            // Only the focused list's selection stands out
+            let tags_focused = *mp_struct.get_scalar_tags_focused();
+            // --- Left side (runs, then the loaded run's scalar tags) ---
+            let runs = mp_struct.get_scalar_runs();
+            let run_items: Vec<ListItem> = runs
+                .iter()
+                .map(|run| ListItem::new(format!(" {}", run.get_label())))
+                .collect();
+            let mut run_list_state = ListState::default();
+            if !runs.is_empty() {
+                run_list_state.select(Some(mp_struct.get_scalar_run_selection()));
+            }
+            let run_list = List::new(run_items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Runs (Enter: load, Esc: back)"),
+                )
+                .highlight_symbol(" > ")
+                .highlight_style(highlight(!tags_focused));
+            f.render_stateful_widget(run_list, left_chunk[0], &mut run_list_state);
+
+            let scalars = mp_struct.get_scalars();
+            let tag_items: Vec<ListItem> = scalars
+                .keys()
+                .map(|tag| ListItem::new(format!(" {}", tag)))
+                .collect();
+            let mut tag_list_state = ListState::default();
+            if !scalars.is_empty() {
+                tag_list_state.select(Some(mp_struct.get_scalar_tag_selection()));
+            }
+            let tag_list = List::new(tag_items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title("Scalars (Tab: switch list)"),
+                )
+                .highlight_symbol(" > ")
+                .highlight_style(highlight(tags_focused));
+            f.render_stateful_widget(tag_list, left_chunk[1], &mut tag_list_state);
+
+            // --- Right side (the selected tag over steps) ---
+            let chart_block = Block::default().borders(Borders::ALL);
+            let Some((tag, series)) = mp_struct.get_selected_scalar() else {
+                let message = match mp_struct.get_scalars_message() {
+                    "" => "Select a run and press Enter to load its scalars.",
+                    message => message,
+                };
+                let paragraph = Paragraph::new(Line::from(message))
+                    .alignment(Alignment::Center)
+                    .block(chart_block.title("Chart"));
+                f.render_widget(paragraph, layout[1]);
+                return;
+            };
+
+            let points: Vec<(f64, f64)> = series
+                .iter()
+                .map(|(step, value)| (*step as f64, *value))
+                .filter(|(_, value)| value.is_finite())
+                .collect();
+            let (x_min, x_max) = bounds(points.iter().map(|(step, _)| *step));
+            let (y_min, y_max) = bounds(points.iter().map(|(_, value)| *value));
+            let latest = points
+                .last()
+                .map(|(step, value)| format!("  last {:.4} @{}", value, step))
+                .unwrap_or_default();
+
+            let dataset = Dataset::default()
+                .marker(Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Cyan))
+                .data(&points);
+            let axis_labels = |min: f64, max: f64| {
+                vec![
+                    Span::raw(format!("{:.2}", min)),
+                    Span::raw(format!("{:.2}", (min + max) / 2.0)),
+                    Span::raw(format!("{:.2}", max)),
+                ]
+            };
+            let chart = Chart::new(vec![dataset])
+                .block(chart_block.title(format!("{}{}", tag, latest)))
+                .x_axis(
+                    Axis::default()
+                        .title("step")
+                        .bounds([x_min, x_max])
+                        .labels(axis_labels(x_min, x_max)),
+                )
+                .y_axis(
+                    Axis::default()
+                        .bounds([y_min, y_max])
+                        .labels(axis_labels(y_min, y_max)),
+                );
+            f.render_widget(chart, layout[1]);
+        })
+        .unwrap();
+}
+
+// Smallest and largest value, widened when they are equal so the axis has a span
+fn bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+        (min.min(value), max.max(value))
+    });
+    if min > max {
+        (0.0, 1.0) // No points
+    } else if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    }
+}
@@ -1,18 +1,17 @@
-use super::Mainpage;
+use super::{Mainpage, status_span};
 use ratatui::{
     Terminal,
-    backend::CrosstermBackend,
     layout::Alignment,
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
-    text::{Line, Span, Text},
+    text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
 use serde_yaml::Value;
 use std::fmt::Write;
 
 // Helper function to convert Value to lines for display.
-fn yaml_to_lines(yaml_value: &Value) -> Vec<String> {
+pub(super) fn yaml_to_lines(yaml_value: &Value) -> Vec<String> {
     let mut lines = Vec::new();
     traverse_yaml(yaml_value, &mut lines, 0);
     lines
@@ -177,11 +176,12 @@ pub fn render_yaml_page_ui<B: ratatui::backend::Backend>(
 
             let task_items: Vec<ListItem> = {
                 let mut items = vec![ListItem::new(" ")]; // blank line after title
-                items.extend(
-                    task_names
-                        .iter()
-                        .map(|task| ListItem::new(Span::raw(format!("    {}", task)))),
-                );
+                items.extend(task_names.iter().enumerate().map(|(i, task)| {
+                    ListItem::new(Line::from(vec![
+                        Span::raw(format!("    {}", task)),
+                        status_span(mp_struct.get_task_statuses().get(i)),
+                    ]))
+                }));
                 items
             };
 